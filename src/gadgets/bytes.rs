@@ -0,0 +1,2004 @@
+use super::{
+    helpers,
+    int16::Int16,
+    int8::Int8,
+    traits::{ArithmeticGadget, BitwiseOperationGadget, IntGadget},
+    ByteSponge,
+};
+use anyhow::{anyhow, ensure, Result};
+use ark_ff::{Field, PrimeField};
+use ark_r1cs_std::{
+    fields::fp::FpVar,
+    prelude::{AllocVar, Boolean, EqGadget, FieldVar},
+    select::CondSelectGadget,
+    R1CSVar,
+};
+use ark_relations::{
+    lc,
+    r1cs::{LinearCombination, SynthesisError},
+};
+
+/// Extracts the raw (two's complement) representation of each byte's value,
+/// bridging in-circuit bytes to host buffers.
+pub fn slice_to_bytes<F: Field>(bytes: &[Int8<F>]) -> Result<Vec<u8>, SynthesisError> {
+    bytes
+        .iter()
+        .map(|byte| Ok(u8::from_ne_bytes(byte.value()?.to_ne_bytes())))
+        .collect()
+}
+
+/// Allocates `expected` as public inputs and enforces that `bytes` equals it
+/// element-wise, so a verifier can check a committed output (e.g. a digest).
+pub fn enforce_bytes_equal_public<F: Field>(bytes: &[Int8<F>], expected: &[i8]) -> Result<()> {
+    ensure!(
+        bytes.len() == expected.len(),
+        "Expected {} bytes but got {}",
+        expected.len(),
+        bytes.len()
+    );
+    let cs = bytes.cs();
+    for (byte, expected_byte) in bytes.iter().zip(expected) {
+        let public_byte = Int8::new_input(cs.clone(), || Ok(*expected_byte))?;
+        byte.enforce_equal(&public_byte)?;
+    }
+    Ok(())
+}
+
+/// Enforces that no two elements of `bytes` are equal.
+///
+/// Every pair is compared, so this adds `n * (n - 1) / 2` inequality checks;
+/// for large slices prefer a sorting or permutation based argument. Pairs of
+/// constants are compared natively and fail with an error if equal.
+pub fn enforce_all_distinct<F: Field>(bytes: &[Int8<F>]) -> Result<()> {
+    for (i, byte) in bytes.iter().enumerate() {
+        for other_byte in bytes.iter().skip(i + 1) {
+            if byte.is_constant() && other_byte.is_constant() {
+                ensure!(
+                    byte.value()? != other_byte.value()?,
+                    "The constant byte {} appears more than once",
+                    byte.value()?
+                );
+            } else {
+                byte.conditional_enforce_not_equal(other_byte, &Boolean::TRUE)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Enforces that `permuted` is a permutation of `original`.
+///
+/// For every element of `original` its multiplicity in both slices is counted
+/// with equality checks and both counts are enforced to be equal. Together with
+/// both slices having the same length this implies they are the same multiset.
+/// This adds `2 * n^2` equality checks. When both slices are constant their
+/// multiplicities are compared natively instead.
+pub fn enforce_permutation<F: Field>(original: &[Int8<F>], permuted: &[Int8<F>]) -> Result<()> {
+    ensure!(
+        original.len() == permuted.len(),
+        "A permutation must have the same length as the original slice"
+    );
+    let cs = original.cs().or(permuted.cs());
+    if cs.is_none() {
+        let mut original_values = original.value()?;
+        let mut permuted_values = permuted.value()?;
+        original_values.sort_unstable();
+        permuted_values.sort_unstable();
+        ensure!(
+            original_values == permuted_values,
+            "The constant slices are not a permutation of each other"
+        );
+        return Ok(());
+    }
+    for byte in original {
+        let mut multiplicity_difference: LinearCombination<F> = lc!();
+        for other_byte in original {
+            multiplicity_difference = multiplicity_difference + byte.is_eq(other_byte)?.lc();
+        }
+        for other_byte in permuted {
+            multiplicity_difference = multiplicity_difference - byte.is_eq(other_byte)?.lc();
+        }
+        cs.enforce_constraint(lc!(), lc!(), multiplicity_difference)?;
+    }
+    Ok(())
+}
+
+/// Counts how many bytes fall in each of `bins` equally wide ranges covering
+/// `i8::MIN..=i8::MAX`, the first bin starting at `i8::MIN`. When 256 is not a
+/// multiple of `bins` the bins are `ceil(256 / bins)` wide and the trailing
+/// ones may be empty.
+pub fn histogram<F: Field>(bytes: &[Int8<F>], bins: usize) -> Result<Vec<Int8<F>>> {
+    ensure!(
+        (1..=256).contains(&bins),
+        "The number of bins must be between 1 and 256"
+    );
+    ensure!(
+        bytes.len() <= usize::try_from(i8::MAX)?,
+        "Too many bytes, the bin counts would overflow"
+    );
+    let bin_width = i16::try_from((256 + bins - 1) / bins)?;
+    let one = Int8::constant(1);
+
+    let mut histogram = Vec::with_capacity(bins);
+    for bin in 0..bins {
+        let mut count = Int8::constant(0);
+        let lower_bound = i16::from(i8::MIN) + i16::try_from(bin)? * bin_width;
+        if let Ok(lower_bound) = i8::try_from(lower_bound) {
+            let upper_bound =
+                i8::try_from(i16::from(lower_bound) + bin_width - 1).unwrap_or(i8::MAX);
+            for byte in bytes {
+                let is_in_bin = byte.is_in_range(lower_bound, upper_bound)?;
+                count = Int8::conditionally_select(&is_in_bin, &count.add(&one)?, &count)?;
+            }
+        }
+        histogram.push(count);
+    }
+    Ok(histogram)
+}
+
+/// Folds `bytes` into a single byte by rotating the digest one bit to the
+/// left and xoring the next byte into it. This is NOT a cryptographic hash,
+/// only a cheap in-circuit fingerprint. An empty slice hashes to zero.
+pub fn fold_hash<F: Field>(bytes: &[Int8<F>]) -> Result<Int8<F>> {
+    let mut digest = Int8::constant(0);
+    for byte in bytes {
+        digest = digest.rotate_left_free(1)?.xor(byte)?;
+    }
+    Ok(digest)
+}
+
+/// Enforces that `bytes` is sorted in ascending (non-decreasing) signed order
+/// by checking that no element is less than the one before it.
+pub fn enforce_sorted_ascending<F: Field>(bytes: &[Int8<F>]) -> Result<()> {
+    for (previous, next) in bytes.iter().zip(bytes.iter().skip(1)) {
+        next.is_lt(previous)?.enforce_equal(&Boolean::FALSE)?;
+    }
+    Ok(())
+}
+
+/// Enforces that `bytes` is strictly increasing in signed order, which also
+/// implies that all its elements are distinct.
+pub fn enforce_strictly_increasing<F: Field>(bytes: &[Int8<F>]) -> Result<()> {
+    for (previous, next) in bytes.iter().zip(bytes.iter().skip(1)) {
+        previous.is_lt(next)?.enforce_equal(&Boolean::TRUE)?;
+    }
+    Ok(())
+}
+
+/// Returns the index of the maximum element of `bytes`. Ties resolve to the
+/// lowest index.
+pub fn argmax<F: Field>(bytes: &[Int8<F>]) -> Result<Int8<F>> {
+    ensure!(
+        bytes.len() <= 128,
+        "Too many bytes, the index would overflow an Int8"
+    );
+    let mut max = bytes
+        .first()
+        .ok_or_else(|| anyhow!("Cannot compute the argmax of an empty slice"))?
+        .clone();
+    let mut max_index = Int8::constant(0);
+    for (index, byte) in bytes.iter().enumerate().skip(1) {
+        // A strict comparison keeps the first maximum on ties.
+        let is_greater = max.is_lt(byte)?;
+        max = Int8::conditionally_select(&is_greater, byte, &max)?;
+        max_index = Int8::conditionally_select(
+            &is_greater,
+            &Int8::constant(i8::try_from(index)?),
+            &max_index,
+        )?;
+    }
+    Ok(max_index)
+}
+
+/// Returns `if_true` when `cond` holds and `if_false` otherwise, so call
+/// sites read like `cond ? if_true : if_false`.
+pub fn select<F: Field>(
+    cond: &Boolean<F>,
+    if_true: &Int8<F>,
+    if_false: &Int8<F>,
+) -> Result<Int8<F>> {
+    Ok(Int8::conditionally_select(cond, if_true, if_false)?)
+}
+
+/// Selects `true_value` when `cond` holds and `false_value` otherwise, byte
+/// by byte. Both slices must have the same length.
+pub fn conditionally_select_slice<F: Field>(
+    cond: &Boolean<F>,
+    true_value: &[Int8<F>],
+    false_value: &[Int8<F>],
+) -> Result<Vec<Int8<F>>> {
+    ensure!(
+        true_value.len() == false_value.len(),
+        "Cannot select between slices of different lengths"
+    );
+    true_value
+        .iter()
+        .zip(false_value)
+        .map(|(t, f)| Ok(Int8::conditionally_select(cond, t, f)?))
+        .collect()
+}
+
+/// Returns the element of `bytes` at the in-circuit `index`, or zero if the
+/// index is out of bounds. Adds one equality check and select per element.
+fn select_by_index<F: Field>(bytes: &[Int8<F>], index: &Int8<F>) -> Result<Int8<F>> {
+    let mut selected = Int8::constant(0);
+    for (i, byte) in bytes.iter().enumerate() {
+        let is_selected = index.is_eq(&Int8::constant(i8::try_from(i)?))?;
+        selected = Int8::conditionally_select(&is_selected, byte, &selected)?;
+    }
+    Ok(selected)
+}
+
+/// Returns the permutation of indices that sorts `bytes` in ascending order,
+/// ties keeping their original order.
+///
+/// The prover supplies the indices and we enforce that they are a permutation
+/// of `0..n`, that gathering `bytes` through them yields a sorted slice and
+/// that equal bytes appear in increasing index order. This adds O(n^2)
+/// constraints.
+pub fn argsort<F: Field>(bytes: &[Int8<F>]) -> Result<Vec<Int8<F>>> {
+    ensure!(
+        bytes.len() <= 128,
+        "Too many bytes, the indices would overflow an Int8"
+    );
+    let indices = new_sort_order_witness(bytes)?;
+    enforce_stable_sort_order(bytes, &indices)?;
+
+    Ok(indices)
+}
+
+/// Allocates as witnesses the indices that stably sort `bytes`, without
+/// enforcing anything about them.
+fn new_sort_order_witness<F: Field>(bytes: &[Int8<F>]) -> Result<Vec<Int8<F>>> {
+    let cs = bytes.cs();
+    let order = bytes.value().ok().map(|values| {
+        let mut order = (0..values.len()).collect::<Vec<usize>>();
+        order.sort_by_key(|i| values.get(*i).copied());
+        order
+    });
+
+    let mut indices = Vec::with_capacity(bytes.len());
+    for i in 0..bytes.len() {
+        indices.push(Int8::new_witness(cs.clone(), || {
+            let index = order
+                .as_ref()
+                .and_then(|order| order.get(i))
+                .copied()
+                .ok_or(SynthesisError::AssignmentMissing)?;
+            i8::try_from(index).map_err(|_e| SynthesisError::Unsatisfiable)
+        })?);
+    }
+    Ok(indices)
+}
+
+/// Enforces that `order` is the permutation of indices that stably sorts
+/// `bytes` in ascending order.
+fn enforce_stable_sort_order<F: Field>(bytes: &[Int8<F>], order: &[Int8<F>]) -> Result<()> {
+    let positions = (0..bytes.len())
+        .map(|i| Ok(Int8::constant(i8::try_from(i)?)))
+        .collect::<Result<Vec<Int8<F>>>>()?;
+    enforce_permutation(order, &positions)?;
+
+    let sorted = order
+        .iter()
+        .map(|index| select_by_index(bytes, index))
+        .collect::<Result<Vec<Int8<F>>>>()?;
+    enforce_sorted_ascending(&sorted)?;
+
+    // Indices are below 128 so comparing them as signed bytes is fine.
+    for ((previous, next), (previous_index, next_index)) in sorted
+        .iter()
+        .zip(sorted.iter().skip(1))
+        .zip(order.iter().zip(order.iter().skip(1)))
+    {
+        previous_index
+            .is_lt(next_index)?
+            .conditional_enforce_equal(&Boolean::TRUE, &previous.is_eq(next)?)?;
+    }
+    Ok(())
+}
+
+/// Truncates `bytes` to `len` elements or pads it on the right with the
+/// constant `pad` until it has `len` elements. This adds no constraints.
+pub fn resize_bytes<F: Field>(bytes: &[Int8<F>], len: usize, pad: i8) -> Vec<Int8<F>> {
+    let mut resized = bytes.iter().take(len).cloned().collect::<Vec<Int8<F>>>();
+    resized.resize(len, Int8::constant(pad));
+    resized
+}
+
+/// Returns whether `bytes` ends in valid PKCS#7 padding for the given block
+/// size: the last byte `n`, read as unsigned, lies in `1..=block` and the
+/// last `n` bytes all equal `n`. `bytes` must be a non-empty multiple of
+/// `block`, which must fit in a byte.
+pub fn validate_pkcs7<F: Field>(bytes: &[Int8<F>], block: usize) -> Result<Boolean<F>> {
+    ensure!(
+        (1..=255).contains(&block),
+        "PKCS#7 block size must be between 1 and 255"
+    );
+    ensure!(
+        !bytes.is_empty() && bytes.len() % block == 0,
+        "PKCS#7 padded data must be a non-empty multiple of the block size"
+    );
+    let last = bytes
+        .last()
+        .ok_or_else(|| anyhow!("Cannot validate the padding of an empty slice"))?;
+
+    // `pads_at_least[i]` holds whether the padding length is at least `i + 1`.
+    let mut pads_at_least = Vec::with_capacity(block);
+    let mut is_longer = Boolean::FALSE;
+    for length in (1..=block).rev() {
+        let padding_byte = Int8::constant(i8::from_ne_bytes([u8::try_from(length)?]));
+        is_longer = is_longer.or(&last.is_eq(&padding_byte)?)?;
+        pads_at_least.push(is_longer.clone());
+    }
+    pads_at_least.reverse();
+
+    let mut is_valid = pads_at_least
+        .first()
+        .cloned()
+        .ok_or_else(|| anyhow!("Block size must not be zero"))?;
+    for (byte, is_padding) in bytes.iter().rev().zip(pads_at_least) {
+        let matches = byte.is_eq(last)?;
+        is_valid = is_valid.and(&is_padding.not().or(&matches)?)?;
+    }
+    Ok(is_valid)
+}
+
+/// Compares two byte slices lexicographically from the front, reading each
+/// byte as unsigned, and returns `(a < b, a == b)`.
+///
+/// The shorter slice is compared as if padded with zeros on the right, so
+/// slices differing only by trailing zeros compare as equal.
+pub fn lex_compare<F: Field>(a: &[Int8<F>], b: &[Int8<F>]) -> Result<(Boolean<F>, Boolean<F>)> {
+    let len = a.len().max(b.len());
+    let mut is_less = Boolean::FALSE;
+    let mut is_equal = Boolean::TRUE;
+    for (a_byte, b_byte) in resize_bytes(a, len, 0)
+        .iter()
+        .zip(resize_bytes(b, len, 0).iter())
+    {
+        let byte_is_less = helpers::is_less_than_unsigned(a_byte.bits(), b_byte.bits())?;
+        is_less = is_less.or(&is_equal.and(&byte_is_less)?)?;
+        is_equal = is_equal.and(&a_byte.is_eq(b_byte)?)?;
+    }
+    Ok((is_less, is_equal))
+}
+
+/// Adds two multi-byte two's complement integers given as little-endian
+/// bytes, wrapping on overflow. Returns the bytes of the sum and whether the
+/// signed addition overflowed, which is determined by the top bytes.
+pub fn add_bytes_signed<F: Field>(
+    a: &[Int8<F>],
+    b: &[Int8<F>],
+) -> Result<(Vec<Int8<F>>, Boolean<F>)> {
+    ensure!(
+        a.len() == b.len(),
+        "Cannot add integers with different numbers of bytes"
+    );
+    ensure!(!a.is_empty(), "Cannot add integers without bytes");
+
+    let mut sum = Vec::with_capacity(a.len());
+    let mut carry = Boolean::FALSE;
+    for (a_byte, b_byte) in a.iter().zip(b) {
+        let (sum_bits, carry_out) = helpers::ripple_carry_add(a_byte.bits(), b_byte.bits(), carry)?;
+        sum.push(Int8::from_bits_le(&sum_bits)?);
+        carry = carry_out;
+    }
+
+    let (a_sign, b_sign, sum_sign) = match (a.last(), b.last(), sum.last()) {
+        (Some(a_top), Some(b_top), Some(sum_top)) => {
+            (a_top.sign()?, b_top.sign()?, sum_top.sign()?)
+        }
+        _ => return Err(anyhow!("Error getting the top bytes")),
+    };
+    // The addition overflows iff both operands have the same sign and the sign
+    // of the sum differs from it.
+    let overflow = a_sign.xor(&b_sign)?.not().and(&sum_sign.xor(&a_sign)?)?;
+    Ok((sum, overflow))
+}
+
+/// Left folds `items` into `init` with the binary gadget `op`, i.e. computes
+/// `op(...op(op(init, items[0]), items[1])..., items[n - 1])`.
+pub fn reduce<F: Field>(
+    items: &[Int8<F>],
+    init: Int8<F>,
+    op: impl Fn(&Int8<F>, &Int8<F>) -> Result<Int8<F>>,
+) -> Result<Int8<F>> {
+    items.iter().try_fold(init, |acc, item| op(&acc, item))
+}
+
+/// Sums the bytes yielded by `bytes` with wrapping addition, one at a time.
+/// An empty iterator sums to zero.
+pub fn fold_add<F: Field>(bytes: impl IntoIterator<Item = Int8<F>>) -> Result<Int8<F>> {
+    bytes
+        .into_iter()
+        .try_fold(Int8::constant(0), |sum, byte| Int8::addmany(&[sum, byte]))
+}
+
+/// Applies the gadget `f` to every consecutive block of `N` bytes, e.g. the
+/// compression function of a block hash. The length of `bytes` must be a
+/// multiple of `N`.
+pub fn process_in_chunks<F: Field, const N: usize>(
+    bytes: &[Int8<F>],
+    f: impl Fn(&[Int8<F>; N]) -> Result<Int8<F>>,
+) -> Result<Vec<Int8<F>>> {
+    ensure!(N > 0, "Chunks must not be empty");
+    ensure!(
+        bytes.len() % N == 0,
+        "Cannot split {} bytes into chunks of {}",
+        bytes.len(),
+        N
+    );
+    bytes
+        .chunks_exact(N)
+        .map(|chunk| f(<&[Int8<F>; N]>::try_from(chunk)?))
+        .collect()
+}
+
+/// Folds `deltas` into a running count starting at zero, enforcing that the
+/// count never goes negative, and returns the final count. Each partial sum
+/// is computed over 9 bits so it cannot wrap, and is also constrained to stay
+/// below 128 so that it fits back in an Int8.
+pub fn enforce_counter_non_negative<F: Field>(deltas: &[Int8<F>]) -> Result<Int8<F>> {
+    let mut count = Int8::constant(0);
+    for delta in deltas {
+        let (sum, _) = helpers::ripple_carry_add(
+            &helpers::sign_extend(count.bits(), 9)?,
+            &helpers::sign_extend(delta.bits(), 9)?,
+            Boolean::FALSE,
+        )?;
+        let (count_bits, upper_bits) = sum.split_at(7);
+        // Both the sign bit and the bit above must be cleared.
+        for bit in upper_bits {
+            bit.enforce_equal(&Boolean::FALSE)?;
+        }
+        count = Int8::pack_bits(count_bits)?;
+    }
+    Ok(count)
+}
+
+/// Rotates `bytes` left by the in-circuit `amount`, which is enforced to be
+/// in `0..bytes.len()`, as `slice::rotate_left` would. Every output byte is
+/// selected among all the possible rotations, so this costs O(n^2) selects.
+pub fn conditional_rotate_array<F: Field>(
+    bytes: &[Int8<F>],
+    amount: &Int8<F>,
+) -> Result<Vec<Int8<F>>> {
+    ensure!(
+        bytes.len() <= 128,
+        "Too many bytes, the rotation amount would overflow an Int8"
+    );
+    let mut rotates_by = Vec::with_capacity(bytes.len());
+    for rotation in 0..bytes.len() {
+        rotates_by.push(amount.is_eq(&Int8::constant(i8::try_from(rotation)?))?);
+    }
+    if !bytes.is_empty() {
+        Boolean::kary_or(&rotates_by)?.enforce_equal(&Boolean::TRUE)?;
+    }
+
+    let mut rotated = Vec::with_capacity(bytes.len());
+    for i in 0..bytes.len() {
+        let mut selected = Int8::constant(0);
+        for (rotation, is_rotation) in rotates_by.iter().enumerate() {
+            let byte = bytes
+                .get((i + rotation) % bytes.len())
+                .ok_or_else(|| anyhow!("Error getting the rotated byte"))?;
+            selected = Int8::conditionally_select(is_rotation, byte, &selected)?;
+        }
+        rotated.push(selected);
+    }
+    Ok(rotated)
+}
+
+/// Returns the inclusive prefix XOR of `bytes`, i.e. the `i`-th output is the
+/// XOR of `bytes[0..=i]`.
+pub fn xor_scan<F: Field>(bytes: &[Int8<F>]) -> Result<Vec<Int8<F>>> {
+    let mut prefixes: Vec<Int8<F>> = Vec::with_capacity(bytes.len());
+    for byte in bytes {
+        let prefix = match prefixes.last() {
+            Some(previous) => previous.xor(byte)?,
+            None => byte.clone(),
+        };
+        prefixes.push(prefix);
+    }
+    Ok(prefixes)
+}
+
+/// Enforces that run-length decoding `pairs` of `(count, value)` yields
+/// `expected`, i.e. that `expected` is made of `count` copies of each `value`
+/// in order. Counts are read as unsigned bytes and runs may be empty.
+///
+/// The end of every run is accumulated over 16 bits and each expected byte is
+/// checked against the value of the run covering its position, which costs
+/// O(n * m) constraints for `n` pairs and `m` expected bytes.
+pub fn enforce_rle_decodes_to<F: Field>(
+    pairs: &[(Int8<F>, Int8<F>)],
+    expected: &[Int8<F>],
+) -> Result<()> {
+    ensure!(
+        pairs.len() <= 256,
+        "Too many pairs, the decoded length could overflow 16 bits"
+    );
+    let expected_length = u64::try_from(expected.len())?;
+    ensure!(
+        expected_length < 1_u64 << 16_u32,
+        "Too many expected bytes for a 16-bit decoded length"
+    );
+    let zero_extend = |bits: &[Boolean<F>]| {
+        let mut extended = bits.to_vec();
+        extended.resize(16, Boolean::FALSE);
+        extended
+    };
+
+    let mut run_ends = Vec::with_capacity(pairs.len());
+    let mut decoded_length = vec![Boolean::FALSE; 16];
+    for (count, _) in pairs {
+        (decoded_length, _) =
+            helpers::ripple_carry_add(&decoded_length, &zero_extend(count.bits()), Boolean::FALSE)?;
+        run_ends.push(decoded_length.clone());
+    }
+    decoded_length.enforce_equal(&helpers::constant_bits_le(expected_length, 16))?;
+
+    for (position, byte) in (0_u64..).zip(expected) {
+        let position_bits = helpers::constant_bits_le(position, 16);
+        // The byte belongs to the first run ending after its position.
+        let mut is_covered = Boolean::FALSE;
+        for ((_, value), run_end) in pairs.iter().zip(&run_ends) {
+            let ends_after = helpers::is_less_than_unsigned(&position_bits, run_end)?;
+            let is_in_run = ends_after.and(&is_covered.not())?;
+            byte.conditional_enforce_equal(value, &is_in_run)?;
+            is_covered = ends_after;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the `n x n` matrix whose entry `[i][j]` is `bytes[i] < bytes[j]`
+/// (signed), e.g. to rank the elements by counting each row. Every
+/// off-diagonal entry is a separate comparison, so this costs O(n^2)
+/// constraints; the diagonal is constant false.
+pub fn comparison_matrix<F: Field>(bytes: &[Int8<F>]) -> Result<Vec<Vec<Boolean<F>>>> {
+    bytes
+        .iter()
+        .enumerate()
+        .map(|(i, left)| {
+            bytes
+                .iter()
+                .enumerate()
+                .map(|(j, right)| {
+                    if i == j {
+                        Ok(Boolean::FALSE)
+                    } else {
+                        left.is_lt(right)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Enforces that `bytes` is a permutation of `lo..=hi`: it has exactly one
+/// element per integer in the range, every element is in the range and no two
+/// elements are equal. Errors if the length doesn't match the range.
+pub fn enforce_permutation_of_range<F: Field>(bytes: &[Int8<F>], lo: i8, hi: i8) -> Result<()> {
+    ensure!(lo <= hi, "Empty range {}..={}", lo, hi);
+    let range_length = usize::try_from(i16::from(hi) - i16::from(lo) + 1)?;
+    ensure!(
+        bytes.len() == range_length,
+        "Expected {} bytes for the range {}..={} but got {}",
+        range_length,
+        lo,
+        hi,
+        bytes.len()
+    );
+    for byte in bytes {
+        byte.is_in_range(lo, hi)?.enforce_equal(&Boolean::TRUE)?;
+    }
+    enforce_all_distinct(bytes)
+}
+
+/// Counts the bits that differ between `a` and `b` into an Int16. Errors if
+/// the slices have different lengths.
+pub fn slice_hamming_distance<F: Field>(a: &[Int8<F>], b: &[Int8<F>]) -> Result<Int16<F>> {
+    ensure!(
+        a.len() == b.len(),
+        "Cannot compare slices of lengths {} and {}",
+        a.len(),
+        b.len()
+    );
+    // 8 * 4095 is the largest such distance that fits in an i16.
+    ensure!(
+        a.len() <= 4095,
+        "Slices too long, the distance could overflow an Int16"
+    );
+    let mut differing_bits = Vec::with_capacity(a.len() * 8);
+    for (a_byte, b_byte) in a.iter().zip(b) {
+        for (a_bit, b_bit) in a_byte.bits.iter().zip(b_byte.bits.iter()) {
+            differing_bits.push(a_bit.xor(b_bit)?);
+        }
+    }
+    let mut distance = helpers::count_ones(&differing_bits)?;
+    distance.resize(16, Boolean::FALSE);
+    Int16::from_bits_le(&distance)
+}
+
+/// Returns the smaller of `a` and `b` together with its index, `ai` or `bi`.
+/// Ties resolve to `a`.
+pub fn min_with_index<F: Field>(
+    a: &Int8<F>,
+    ai: &Int8<F>,
+    b: &Int8<F>,
+    bi: &Int8<F>,
+) -> Result<(Int8<F>, Int8<F>)> {
+    let b_is_smaller = b.is_lt(a)?;
+    Ok((
+        Int8::conditionally_select(&b_is_smaller, b, a)?,
+        Int8::conditionally_select(&b_is_smaller, bi, ai)?,
+    ))
+}
+
+/// Enforces that `a` and `b` hold the same bytes with the same multiplicities,
+/// in any order. Errors if the lengths differ.
+///
+/// Both slices are hashed into a random challenge `x` and we enforce
+/// `prod(x - a_i) = prod(x - b_i)`. Both sides are polynomials in `x` which
+/// only agree everywhere if their roots do, and otherwise agree on a random
+/// `x` with negligible probability. This is linear in the length, unlike
+/// sorting.
+pub fn enforce_multiset_equal<F: PrimeField>(a: &[Int8<F>], b: &[Int8<F>]) -> Result<()> {
+    ensure!(
+        a.len() == b.len(),
+        "Slices of lengths {} and {} can't hold the same multiset",
+        a.len(),
+        b.len()
+    );
+    let mut sponge = ByteSponge::new(a.cs().or(b.cs()), b"multiset equality")?;
+    sponge.absorb_bytes(a)?;
+    sponge.absorb_bytes(b)?;
+    let challenge = sponge.squeeze_field_element()?;
+
+    let grand_product = |bytes: &[Int8<F>]| -> Result<FpVar<F>> {
+        bytes.iter().try_fold(FpVar::one(), |product, byte| {
+            Ok(product * (&challenge - Boolean::le_bits_to_fp_var(byte.bits())?))
+        })
+    };
+    grand_product(a)?.enforce_equal(&grand_product(b)?)?;
+    Ok(())
+}
+
+/// Computes the dot product of `a` and `b`, read as unsigned, modulo
+/// `modulus`. The running sum is reduced after every term, so intermediate
+/// values stay below 2^17. Errors if the lengths differ or `modulus` is zero.
+pub fn dot_product_mod<F: Field>(a: &[Int8<F>], b: &[Int8<F>], modulus: u8) -> Result<Int8<F>> {
+    ensure!(
+        a.len() == b.len(),
+        "Cannot multiply vectors of lengths {} and {}",
+        a.len(),
+        b.len()
+    );
+    ensure!(modulus != 0, "The modulus can't be zero");
+    let mut sum = vec![Boolean::FALSE; 8];
+    for (a_byte, b_byte) in a.iter().zip(b) {
+        let product = helpers::unsigned_mul(a_byte.bits(), b_byte.bits())?;
+        sum.resize(16, Boolean::FALSE);
+        let (partial_sum, carry) = helpers::ripple_carry_add(&product, &sum, Boolean::FALSE)?;
+        (_, sum) =
+            helpers::unsigned_div_rem_constant(&[partial_sum, vec![carry]].concat(), modulus)?;
+    }
+    Int8::from_bits_le(&sum)
+}
+
+/// Enforces that `bytes`, a little-endian integer, has no redundant leading
+/// zero bytes: the most significant byte must be non zero unless it is the
+/// only one. Errors on an empty slice.
+pub fn enforce_canonical_le<F: Field>(bytes: &[Int8<F>]) -> Result<()> {
+    let (most_significant, rest) = bytes
+        .split_last()
+        .ok_or_else(|| anyhow!("An empty slice is not an integer encoding"))?;
+    if !rest.is_empty() {
+        most_significant.is_zero()?.enforce_equal(&Boolean::FALSE)?;
+    }
+    Ok(())
+}
+
+/// Returns the XOR of every bit of `bytes`, i.e. whether an odd number of
+/// bits is set. Rather than chaining XORs this takes the lowest bit of the
+/// number of set bits, which costs one linear constraint plus a booleanity
+/// check for each bit of the count.
+pub fn slice_parity<F: Field>(bytes: &[Int8<F>]) -> Result<Boolean<F>> {
+    let bits = bytes
+        .iter()
+        .flat_map(|byte| byte.bits.clone())
+        .collect::<Vec<Boolean<F>>>();
+    Ok(helpers::count_ones(&bits)?
+        .first()
+        .cloned()
+        .unwrap_or(Boolean::FALSE))
+}
+
+/// Searches `sorted` for `target`, returning an index and whether `target`
+/// was found. When found, the index is that of its first occurrence;
+/// otherwise it is where `target` could be inserted keeping the slice sorted,
+/// like `slice::binary_search`. The slice is enforced to be sorted in signed
+/// order.
+///
+/// A circuit can't branch on the data, so instead of halving the range we
+/// compare the target with every element once. For a sorted slice the
+/// insertion point is just the number of elements below the target.
+pub fn binary_search<F: Field>(
+    sorted: &[Int8<F>],
+    target: &Int8<F>,
+) -> Result<(Int8<F>, Boolean<F>)> {
+    ensure!(
+        sorted.len() <= 127,
+        "Too many bytes, the index would overflow an Int8"
+    );
+    enforce_sorted_ascending(sorted)?;
+
+    let mut is_below = Vec::with_capacity(sorted.len());
+    let mut is_match = Vec::with_capacity(sorted.len());
+    for byte in sorted {
+        is_below.push(byte.is_lt(target)?);
+        is_match.push(byte.is_eq(target)?);
+    }
+    let index = Int8::pack_bits(&helpers::count_ones(&is_below)?)?;
+    Ok((index, Boolean::kary_or(&is_match)?))
+}
+
+/// Returns the inclusive prefix sums of `bytes`, widened to Int16 so they
+/// can't overflow.
+pub fn prefix_sum<F: Field>(bytes: &[Int8<F>]) -> Result<Vec<Int16<F>>> {
+    // 256 * i8::MIN is the smallest such sum that fits in an i16.
+    ensure!(
+        bytes.len() <= 256,
+        "Too many bytes, the sum could overflow an Int16"
+    );
+    let mut sum = Int16::constant(0);
+    let mut prefix_sums = Vec::with_capacity(bytes.len());
+    for byte in bytes {
+        let widened_byte = Int16::from_bits_le(&helpers::sign_extend(&byte.bits, 16)?)?;
+        sum = Int16::addmany(&[sum, widened_byte])?;
+        prefix_sums.push(sum.clone());
+    }
+    Ok(prefix_sums)
+}
+
+/// Returns the index of the first byte where `a` and `b` differ and whether
+/// they differ at all. Identical slices yield their length as the index.
+/// Errors if the lengths differ.
+pub fn first_difference<F: Field>(a: &[Int8<F>], b: &[Int8<F>]) -> Result<(Int8<F>, Boolean<F>)> {
+    ensure!(
+        a.len() == b.len(),
+        "Cannot compare slices of lengths {} and {}",
+        a.len(),
+        b.len()
+    );
+    ensure!(
+        a.len() <= 127,
+        "Too many bytes, the index would overflow an Int8"
+    );
+    // The index of the first difference is the length of the common prefix.
+    let mut is_common_prefix = Vec::with_capacity(a.len());
+    let mut all_equal = Boolean::TRUE;
+    for (a_byte, b_byte) in a.iter().zip(b) {
+        all_equal = all_equal.and(&a_byte.is_eq(b_byte)?)?;
+        is_common_prefix.push(all_equal.clone());
+    }
+    let index = Int8::pack_bits(&helpers::count_ones(&is_common_prefix)?)?;
+    Ok((index, all_equal.not()))
+}
+
+/// Returns the weighted median of `values`: the smallest value at which the
+/// cumulative weight of the values up to it reaches half the total weight.
+/// Weights are enforced to be non negative. Errors on empty or mismatched
+/// slices.
+///
+/// The values are sorted as in `argsort`, so this adds O(n^2) constraints.
+pub fn weighted_median<F: Field>(values: &[Int8<F>], weights: &[Int8<F>]) -> Result<Int8<F>> {
+    ensure!(
+        values.len() == weights.len(),
+        "Expected {} weights but got {}",
+        values.len(),
+        weights.len()
+    );
+    ensure!(
+        values.len() <= 128,
+        "Too many values, the indices would overflow an Int8"
+    );
+    weighted_median_in_order(values, weights, &new_sort_order_witness(values)?)
+}
+
+/// Computes the weighted median walking `values` and `weights` in the given
+/// `order`, which is enforced to be the one `argsort` returns.
+fn weighted_median_in_order<F: Field>(
+    values: &[Int8<F>],
+    weights: &[Int8<F>],
+    order: &[Int8<F>],
+) -> Result<Int8<F>> {
+    for weight in weights {
+        weight.sign()?.enforce_equal(&Boolean::FALSE)?;
+    }
+    enforce_stable_sort_order(values, order)?;
+
+    let mut sorted_values = Vec::with_capacity(values.len());
+    let mut sorted_weights = Vec::with_capacity(values.len());
+    for index in order {
+        sorted_values.push(select_by_index(values, index)?);
+        sorted_weights.push(select_by_index(weights, index)?);
+    }
+    let cumulative_weights = prefix_sum(&sorted_weights)?;
+    let total_weight = cumulative_weights
+        .last()
+        .ok_or_else(|| anyhow!("Cannot compute the weighted median of an empty slice"))?;
+
+    // Walking backwards, the last value kept is the first one whose doubled
+    // cumulative weight reaches the total. Doubling can't overflow as the
+    // cumulative weights are below 2^14.
+    let mut median = Int8::constant(0);
+    for (value, cumulative_weight) in sorted_values.iter().zip(&cumulative_weights).rev() {
+        let mut doubled_bits = vec![Boolean::FALSE];
+        doubled_bits.extend(cumulative_weight.bits.iter().take(15).cloned());
+        let reaches_half = Int16::from_bits_le(&doubled_bits)?
+            .is_lt(total_weight)?
+            .not();
+        median = Int8::conditionally_select(&reaches_half, value, &median)?;
+    }
+    Ok(median)
+}
+
+#[cfg(test)]
+mod bytes_tests {
+    use super::{
+        add_bytes_signed, argmax, argsort, binary_search, comparison_matrix,
+        conditional_rotate_array, conditionally_select_slice, dot_product_mod,
+        enforce_all_distinct, enforce_bytes_equal_public, enforce_canonical_le,
+        enforce_counter_non_negative, enforce_multiset_equal, enforce_permutation,
+        enforce_permutation_of_range, enforce_rle_decodes_to, enforce_sorted_ascending,
+        enforce_stable_sort_order, enforce_strictly_increasing, first_difference, fold_add,
+        fold_hash, histogram, lex_compare, min_with_index, prefix_sum, process_in_chunks, reduce,
+        resize_bytes, select, slice_hamming_distance, slice_parity, slice_to_bytes, validate_pkcs7,
+        weighted_median, weighted_median_in_order, xor_scan,
+    };
+    use crate::gadgets::{
+        traits::{ArithmeticGadget, IntGadget},
+        ConstraintF, Int8Gadget,
+    };
+    use ark_r1cs_std::{
+        prelude::{AllocVar, Boolean},
+        select::CondSelectGadget,
+        R1CSVar,
+    };
+    use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+
+    #[test]
+    fn test_slice_to_bytes_round_trip() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let primitive_bytes = [0_u8, 1, 127, 128, 200, 255];
+        let signed_bytes = primitive_bytes.map(|byte| i8::from_ne_bytes([byte]));
+
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &signed_bytes).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(primitive_bytes.to_vec(), slice_to_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn test_enforce_bytes_equal_public_digest() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let digest = [-34_i8, -83, -66, -17];
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &digest).unwrap();
+
+        enforce_bytes_equal_public(&bytes, &digest).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        // One instance variable for the constant one plus one per digest bit.
+        assert_eq!(1 + digest.len() * 8, cs.num_instance_variables());
+    }
+
+    #[test]
+    fn test_enforce_bytes_equal_public_with_wrong_digest_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3, 4]).unwrap();
+
+        enforce_bytes_equal_public(&bytes, &[1, 2, 3, 5]).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_bytes_equal_public_with_length_mismatch_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs, &[1_i8, 2, 3]).unwrap();
+
+        assert!(enforce_bytes_equal_public(&bytes, &[1, 2, 3, 4]).is_err());
+    }
+
+    #[test]
+    fn test_enforce_all_distinct_with_distinct_bytes() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[-1_i8, 0, 1, 2, 127]).unwrap();
+
+        enforce_all_distinct(&bytes).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_all_distinct_with_duplicated_bytes_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[-1_i8, 0, 1, 2, 0]).unwrap();
+
+        enforce_all_distinct(&bytes).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_all_distinct_with_duplicated_constants_fails() {
+        let bytes = [
+            Int8Gadget::constant(1),
+            Int8Gadget::constant(2),
+            Int8Gadget::constant(1),
+        ];
+
+        assert!(enforce_all_distinct(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_enforce_all_distinct_with_distinct_constants() {
+        let bytes = [Int8Gadget::constant(1), Int8Gadget::constant(2)];
+
+        enforce_all_distinct(&bytes).unwrap();
+    }
+
+    #[test]
+    fn test_enforce_permutation_with_a_permutation() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let original = Int8Gadget::new_witness_vec(cs.clone(), &[3_i8, -1, 3, 7, 0]).unwrap();
+        let permuted = Int8Gadget::new_witness_vec(cs.clone(), &[0_i8, 3, 7, -1, 3]).unwrap();
+
+        enforce_permutation(&original, &permuted).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_permutation_with_different_multiplicities_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let original = Int8Gadget::new_witness_vec(cs.clone(), &[3_i8, -1, 3, 7, 0]).unwrap();
+        let permuted = Int8Gadget::new_witness_vec(cs.clone(), &[0_i8, 3, 7, -1, -1]).unwrap();
+
+        enforce_permutation(&original, &permuted).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_permutation_with_different_elements_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let original = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3]).unwrap();
+        let permuted = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 4]).unwrap();
+
+        enforce_permutation(&original, &permuted).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_permutation_with_a_constant_permutation() {
+        let original = [Int8Gadget::constant(1), Int8Gadget::constant(2)];
+        let permuted = [Int8Gadget::constant(2), Int8Gadget::constant(1)];
+
+        enforce_permutation(&original, &permuted).unwrap();
+    }
+
+    #[test]
+    fn test_enforce_permutation_with_a_constant_non_permutation_fails() {
+        let original = [Int8Gadget::constant(1), Int8Gadget::constant(1)];
+        let permuted = [Int8Gadget::constant(1), Int8Gadget::constant(2)];
+
+        assert!(enforce_permutation(&original, &permuted).is_err());
+    }
+
+    #[test]
+    fn test_histogram() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let primitive_bytes = [-128_i8, -1, 0, 5, 64, 127, 100, -65, -64];
+        let bins = 4;
+        let mut expected_histogram = vec![0_i8; bins];
+        for byte in primitive_bytes {
+            let bin = usize::try_from((i16::from(byte) + 128) / 64).unwrap();
+            *expected_histogram.get_mut(bin).unwrap() += 1;
+        }
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+
+        let result = histogram(&bytes, bins).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(expected_histogram, result.value().unwrap());
+    }
+
+    #[test]
+    fn test_histogram_with_no_bins_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs, &[1_i8, 2]).unwrap();
+
+        assert!(histogram(&bytes, 0).is_err());
+    }
+
+    #[test]
+    fn test_fold_hash() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let primitive_bytes = [-34_i8, -83, -66, -17, 0, 1, 127];
+        let expected_digest = primitive_bytes.iter().fold(0_u8, |digest, byte| {
+            digest.rotate_left(1) ^ u8::from_ne_bytes(byte.to_ne_bytes())
+        });
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+
+        let digest = fold_hash(&bytes).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(
+            expected_digest,
+            u8::from_ne_bytes(digest.value().unwrap().to_ne_bytes())
+        );
+    }
+
+    #[test]
+    fn test_enforce_sorted_ascending_with_sorted_bytes() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[-128_i8, -5, 0, 3, 127]).unwrap();
+
+        enforce_sorted_ascending(&bytes).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_sorted_ascending_with_equal_adjacent_bytes() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[-5_i8, -5, 0, 0, 3]).unwrap();
+
+        enforce_sorted_ascending(&bytes).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_sorted_ascending_with_unsorted_bytes_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[-5_i8, 3, 0]).unwrap();
+
+        enforce_sorted_ascending(&bytes).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_sorted_ascending_rejects_tampered_witnesses() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[3_i8, 5]).unwrap();
+
+        enforce_sorted_ascending(&bytes).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        // Swap the bits of the two bytes so that they read [5, 3] while the
+        // rest of the witnesses are still the ones computed for [3, 5].
+        let mut tampered_assignment = cs.borrow().unwrap().witness_assignment.clone();
+        tampered_assignment.get_mut(..16).unwrap().rotate_left(8);
+        cs.borrow_mut().unwrap().witness_assignment = tampered_assignment;
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_argmax() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[-5_i8, 3, 100, -128, 7]).unwrap();
+
+        let index = argmax(&bytes).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(2, index.value().unwrap());
+    }
+
+    #[test]
+    fn test_argmax_with_ties_returns_the_lowest_index() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[-5_i8, 9, 2, 9, 9]).unwrap();
+
+        let index = argmax(&bytes).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(1, index.value().unwrap());
+    }
+
+    #[test]
+    fn test_argmax_of_an_empty_slice_fails() {
+        assert!(argmax::<ConstraintF>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_conditionally_select_slice() {
+        for condition in [true, false] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let true_value = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3, 4]).unwrap();
+            let false_value =
+                Int8Gadget::new_witness_vec(cs.clone(), &[-1_i8, -2, -3, -4]).unwrap();
+            let cond = Boolean::new_witness(cs.clone(), || Ok(condition)).unwrap();
+
+            let result = conditionally_select_slice(&cond, &true_value, &false_value).unwrap();
+
+            let expected = if condition {
+                true_value.value().unwrap()
+            } else {
+                false_value.value().unwrap()
+            };
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, result.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_conditionally_select_slice_with_length_mismatch_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let true_value = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3, 4]).unwrap();
+        let false_value = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3]).unwrap();
+
+        assert!(conditionally_select_slice(&Boolean::TRUE, &true_value, &false_value).is_err());
+    }
+
+    #[test]
+    fn test_argsort() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let primitive_bytes = [5_i8, -3, 9, -3, 0, -128];
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+
+        let indices = argsort(&bytes).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(vec![5, 1, 3, 4, 0, 2], indices.value().unwrap());
+    }
+
+    #[test]
+    fn test_argsort_order_with_swapped_ties_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[5_i8, -3, 9, -3, 0, -128]).unwrap();
+        let order = Int8Gadget::new_witness_vec(cs.clone(), &[5_i8, 3, 1, 4, 0, 2]).unwrap();
+
+        enforce_stable_sort_order(&bytes, &order).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_argsort_order_that_does_not_sort_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[5_i8, -3, 9, -3, 0, -128]).unwrap();
+        let order = Int8Gadget::new_witness_vec(cs.clone(), &[5_i8, 1, 3, 0, 4, 2]).unwrap();
+
+        enforce_stable_sort_order(&bytes, &order).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_resize_bytes_pads_a_shorter_slice() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3]).unwrap();
+        let number_of_constraints = cs.num_constraints();
+
+        let resized = resize_bytes(&bytes, 5, -1);
+
+        assert_eq!(number_of_constraints, cs.num_constraints());
+        assert_eq!(vec![1, 2, 3, -1, -1], resized.value().unwrap());
+    }
+
+    #[test]
+    fn test_resize_bytes_truncates_a_longer_slice() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3, 4]).unwrap();
+
+        let resized = resize_bytes(&bytes, 2, 0);
+
+        assert_eq!(vec![1, 2], resized.value().unwrap());
+        assert!(resize_bytes(&bytes, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_validate_pkcs7_with_valid_padding() {
+        for primitive_bytes in [
+            [7_i8, 7, 7, 7, 7, 7, 7, 7].as_slice(),
+            [1_i8, 2, 3, 4, 5, 6, 7, 1].as_slice(),
+            [1_i8, 2, 3, 4, 4, 4, 4, 4].as_slice(),
+            [1_i8, 2, 3, 4, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8].as_slice(),
+        ] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec(cs.clone(), primitive_bytes).unwrap();
+
+            let is_valid = validate_pkcs7(&bytes, 8).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert!(is_valid.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_validate_pkcs7_with_invalid_padding() {
+        for primitive_bytes in [
+            [1_i8, 2, 3, 4, 5, 6, 7, 0].as_slice(),
+            [1_i8, 2, 3, 4, 5, 6, 7, 9].as_slice(),
+            [1_i8, 2, 3, 4, 5, 6, 7, -1].as_slice(),
+            [1_i8, 2, 3, 4, 5, 3, 4, 3].as_slice(),
+            [1_i8, 2, 3, 4, 5, 6, 4, 4].as_slice(),
+        ] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec(cs.clone(), primitive_bytes).unwrap();
+
+            let is_valid = validate_pkcs7(&bytes, 8).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert!(!is_valid.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_validate_pkcs7_with_a_partial_block_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs, &[1_i8, 2, 3, 3, 3]).unwrap();
+
+        assert!(validate_pkcs7(&bytes, 8).is_err());
+        assert!(validate_pkcs7(&bytes, 0).is_err());
+    }
+
+    #[test]
+    fn test_lex_compare() {
+        for (a, b, expected_is_less, expected_is_equal) in [
+            (vec![1_i8, 2, 3], vec![1_i8, 2, 3], false, true),
+            (vec![1_i8, 2, 3], vec![1_i8, 2, 4], true, false),
+            (vec![1_i8, 3], vec![1_i8, 2, 4], false, false),
+            (vec![1_i8, 2, -1], vec![1_i8, 2, 127], false, false),
+            (vec![0_i8], vec![-128_i8], true, false),
+            (vec![1_i8, 2], vec![1_i8, 2, 1], true, false),
+            (vec![1_i8, 2, 0], vec![1_i8, 2], false, true),
+            (vec![], vec![], false, true),
+        ] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let a = Int8Gadget::new_witness_vec(cs.clone(), &a).unwrap();
+            let b = Int8Gadget::new_witness_vec(cs.clone(), &b).unwrap();
+
+            let (is_less, is_equal) = lex_compare(&a, &b).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected_is_less, is_less.value().unwrap());
+            assert_eq!(expected_is_equal, is_equal.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_add_bytes_signed_with_two_bytes() {
+        let cases = [
+            (1_i16, 2_i16),
+            (255, 1),
+            (-1, 1),
+            (-300, 45),
+            (i16::MAX, 1),
+            (i16::MIN, -1),
+            (i16::MIN, i16::MAX),
+        ];
+        for (a, b) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let to_bytes = |value: i16| value.to_le_bytes().map(|byte| i8::from_ne_bytes([byte]));
+            let a_bytes = Int8Gadget::new_witness_vec(cs.clone(), &to_bytes(a)).unwrap();
+            let b_bytes = Int8Gadget::new_witness_vec(cs.clone(), &to_bytes(b)).unwrap();
+
+            let (sum, overflow) = add_bytes_signed(&a_bytes, &b_bytes).unwrap();
+
+            let (expected, expected_overflow) = a.overflowing_add(b);
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(to_bytes(expected).to_vec(), sum.value().unwrap());
+            assert_eq!(expected_overflow, overflow.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_add_bytes_signed_with_three_bytes() {
+        // (a, b, sum, overflow) as little-endian bytes
+        let cases = [
+            ([-1_i8, -1, 0], [1_i8, 0, 0], [0_i8, 0, 1], false),
+            ([-1_i8, -1, 127], [1_i8, 0, 0], [0_i8, 0, -128], true),
+            ([0_i8, 0, -128], [-1_i8, -1, -1], [-1_i8, -1, 127], true),
+            ([5_i8, 0, -1], [-5_i8, -1, 0], [0_i8, 0, 0], false),
+        ];
+        for (a, b, expected, expected_overflow) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let a = Int8Gadget::new_witness_vec(cs.clone(), &a).unwrap();
+            let b = Int8Gadget::new_witness_vec(cs.clone(), &b).unwrap();
+
+            let (sum, overflow) = add_bytes_signed(&a, &b).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected.to_vec(), sum.value().unwrap());
+            assert_eq!(expected_overflow, overflow.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_add_bytes_signed_with_length_mismatch_fails() {
+        let a = [Int8Gadget::constant(1), Int8Gadget::constant(2)];
+
+        assert!(add_bytes_signed(&a, &a[..1]).is_err());
+        assert!(add_bytes_signed::<ConstraintF>(&[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_reduce_with_add() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, -2, 30, 4]).unwrap();
+
+        let sum = reduce(&bytes, Int8Gadget::constant(10), |acc, byte| acc.add(byte)).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(43, sum.value().unwrap());
+    }
+
+    #[test]
+    fn test_reduce_with_max() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[-7_i8, 12, -128, 5]).unwrap();
+        let max = |acc: &Int8Gadget, byte: &Int8Gadget| {
+            Ok(Int8Gadget::conditionally_select(
+                &acc.is_lt(byte)?,
+                byte,
+                acc,
+            )?)
+        };
+
+        let maximum = reduce(&bytes, Int8Gadget::constant(i8::MIN), max).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(12, maximum.value().unwrap());
+    }
+
+    #[test]
+    fn test_reduce_of_an_empty_slice_returns_init() {
+        let init = Int8Gadget::constant(3);
+
+        let result = reduce(&[], init, |acc, byte| acc.add(byte)).unwrap();
+
+        assert_eq!(3, result.value().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_strictly_increasing() {
+        let cases = [
+            (vec![-128_i8, -5, 0, 3, 127], true),
+            (vec![-5_i8, -5, 0], false),
+            (vec![1_i8, 2, 2, 3], false),
+            (vec![3_i8, 0, -5], false),
+            (vec![127_i8, -128], false),
+            (vec![42_i8], true),
+        ];
+        for (primitive_bytes, is_increasing) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+
+            enforce_strictly_increasing(&bytes).unwrap();
+
+            assert_eq!(is_increasing, cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_process_in_chunks() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes =
+            Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3, 4, -1, -2, 5, 6, 0, 0, 0, 7])
+                .unwrap();
+
+        let sums = process_in_chunks(&bytes, |chunk: &[Int8Gadget; 4]| Int8Gadget::addmany(chunk))
+            .unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(vec![10, 8, 7], sums.value().unwrap());
+    }
+
+    #[test]
+    fn test_process_in_chunks_with_a_partial_chunk_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs, &[1_i8, 2, 3, 4, 5]).unwrap();
+
+        let result =
+            process_in_chunks(&bytes, |chunk: &[Int8Gadget; 4]| Int8Gadget::addmany(chunk));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_enforce_counter_non_negative_with_balanced_deltas() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        // "(()(()))" followed by an arbitrary delta
+        let deltas =
+            Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 1, -1, 1, 1, -1, -1, -1, 5]).unwrap();
+
+        let count = enforce_counter_non_negative(&deltas).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(5, count.value().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_counter_non_negative_when_out_of_range_is_not_satisfied() {
+        for primitive_deltas in [
+            vec![1_i8, -1, -1, 1],
+            vec![-1_i8],
+            vec![100_i8, -128, 28],
+            vec![127_i8, 1, -1],
+        ] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let deltas = Int8Gadget::new_witness_vec(cs.clone(), &primitive_deltas).unwrap();
+
+            enforce_counter_non_negative(&deltas).unwrap();
+
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_conditional_rotate_array() {
+        let primitive_bytes = [1_i8, -2, 3, -4, 5];
+        for amount in 0..5 {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+            let amount_var = Int8Gadget::new_witness(cs.clone(), || Ok(amount)).unwrap();
+
+            let rotated = conditional_rotate_array(&bytes, &amount_var).unwrap();
+
+            let mut expected = primitive_bytes.to_vec();
+            expected.rotate_left(usize::try_from(amount).unwrap());
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, rotated.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_conditional_rotate_array_by_an_out_of_range_amount_is_not_satisfied() {
+        for amount in [5_i8, -1] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, -2, 3, -4, 5]).unwrap();
+            let amount = Int8Gadget::new_witness(cs.clone(), || Ok(amount)).unwrap();
+
+            conditional_rotate_array(&bytes, &amount).unwrap();
+
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_xor_scan() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let primitive_bytes = [0x0f_i8, 0x33, -1, 0x55, -128, 0];
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+
+        let prefixes = xor_scan(&bytes).unwrap();
+
+        let expected = primitive_bytes
+            .iter()
+            .scan(0_i8, |prefix, byte| {
+                *prefix ^= byte;
+                Some(*prefix)
+            })
+            .collect::<Vec<i8>>();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(expected, prefixes.value().unwrap());
+        assert!(xor_scan::<ConstraintF>(&[]).unwrap().is_empty());
+    }
+
+    fn rle_pairs(
+        cs: ConstraintSystemRef<ConstraintF>,
+        pairs: &[(u8, i8)],
+    ) -> Vec<(Int8Gadget, Int8Gadget)> {
+        pairs
+            .iter()
+            .map(|(count, value)| {
+                (
+                    Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([*count])))
+                        .unwrap(),
+                    Int8Gadget::new_witness(cs.clone(), || Ok(*value)).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_enforce_rle_decodes_to_with_a_correct_decoding() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let pairs = rle_pairs(cs.clone(), &[(3, 7), (0, 1), (1, -2), (2, 7)]);
+        let expected = Int8Gadget::new_witness_vec(cs.clone(), &[7_i8, 7, 7, -2, 7, 7]).unwrap();
+
+        enforce_rle_decodes_to(&pairs, &expected).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_rle_decodes_to_with_a_long_run() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let pairs = rle_pairs(cs.clone(), &[(200, 5), (1, 6)]);
+        let mut primitive_expected = vec![5_i8; 200];
+        primitive_expected.push(6);
+        let expected = Int8Gadget::new_witness_vec(cs.clone(), &primitive_expected).unwrap();
+
+        enforce_rle_decodes_to(&pairs, &expected).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_rle_decodes_to_with_an_incorrect_decoding_is_not_satisfied() {
+        let cases = [
+            (vec![(3_u8, 7_i8), (1, -2)], vec![7_i8, 7, -2, -2]),
+            (vec![(3_u8, 7_i8), (1, -2)], vec![7_i8, 7, 7]),
+            (vec![(3_u8, 7_i8), (1, -2)], vec![7_i8, 7, 7, -2, -2]),
+            (vec![(2_u8, 7_i8)], vec![7_i8, 8]),
+        ];
+        for (primitive_pairs, primitive_expected) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let pairs = rle_pairs(cs.clone(), &primitive_pairs);
+            let expected = Int8Gadget::new_witness_vec(cs.clone(), &primitive_expected).unwrap();
+
+            enforce_rle_decodes_to(&pairs, &expected).unwrap();
+
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_comparison_matrix() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let primitive_bytes = [3_i8, -1, 3, 127, -128];
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+
+        let matrix = comparison_matrix(&bytes).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(primitive_bytes.len(), matrix.len());
+        for (row, left) in matrix.iter().zip(primitive_bytes) {
+            let expected = primitive_bytes
+                .iter()
+                .map(|right| left < *right)
+                .collect::<Vec<bool>>();
+            assert_eq!(expected, row.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_permutation_of_range() {
+        let cases = [
+            (vec![3_i8, 1, 4, 2], true),
+            (vec![1_i8, 2, 3, 5], false),
+            (vec![0_i8, 2, 3, 4], false),
+            (vec![1_i8, 2, 2, 4], false),
+        ];
+        for (primitive_bytes, is_permutation) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+
+            enforce_permutation_of_range(&bytes, 1, 4).unwrap();
+
+            assert_eq!(is_permutation, cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_permutation_of_range_with_wrong_length_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs, &[1_i8, 2, 3]).unwrap();
+
+        assert!(enforce_permutation_of_range(&bytes, 1, 4).is_err());
+        assert!(enforce_permutation_of_range(&bytes, 3, 1).is_err());
+    }
+
+    #[test]
+    fn test_slice_hamming_distance() {
+        let cases: [(&[i8], &[i8]); 4] = [
+            (&[], &[]),
+            (&[0, 1, 2], &[0, 1, 2]),
+            (&[0, -1, 0x0F], &[-1, 0, 0x3C]),
+            (&[-128, 127, 85, 7], &[127, -128, -86, 8]),
+        ];
+        for (a, b) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let a_bytes = Int8Gadget::new_witness_vec(cs.clone(), a).unwrap();
+            let b_bytes = Int8Gadget::new_witness_vec(cs.clone(), b).unwrap();
+            let expected: u32 = a
+                .iter()
+                .zip(b)
+                .map(|(a_byte, b_byte)| (a_byte ^ b_byte).count_ones())
+                .sum();
+
+            let distance = slice_hamming_distance(&a_bytes, &b_bytes).unwrap();
+
+            assert_eq!(i16::try_from(expected).unwrap(), distance.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_slice_hamming_distance_with_different_lengths_fails() {
+        let a = [Int8Gadget::constant(1)];
+        let b = [Int8Gadget::constant(1), Int8Gadget::constant(2)];
+
+        assert!(slice_hamming_distance(&a, &b).is_err());
+    }
+
+    #[test]
+    fn test_min_with_index() {
+        // (a, b, expected min, expected index)
+        let cases = [
+            (-3_i8, 7_i8, -3_i8, 0_i8),
+            (7, -3, -3, 1),
+            (5, 5, 5, 0),
+            (-128, 127, -128, 0),
+        ];
+        for (a, b, expected_min, expected_index) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let a = Int8Gadget::new_witness(cs.clone(), || Ok(a)).unwrap();
+            let ai = Int8Gadget::new_witness(cs.clone(), || Ok(0)).unwrap();
+            let b = Int8Gadget::new_witness(cs.clone(), || Ok(b)).unwrap();
+            let bi = Int8Gadget::new_witness(cs.clone(), || Ok(1)).unwrap();
+
+            let (min, index) = min_with_index(&a, &ai, &b, &bi).unwrap();
+
+            assert_eq!(expected_min, min.value().unwrap());
+            assert_eq!(expected_index, index.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_fold_add() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let values = [100_i8, -7, 90, 3, -128, 55];
+        let bytes = values
+            .iter()
+            .map(|v| Int8Gadget::new_witness(cs.clone(), || Ok(*v)).unwrap())
+            .collect::<Vec<_>>();
+        let expected = values
+            .iter()
+            .step_by(2)
+            .fold(0_i8, |sum, v| sum.wrapping_add(*v));
+
+        let sum = fold_add(bytes.into_iter().step_by(2)).unwrap();
+
+        assert_eq!(expected, sum.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_fold_add_empty_iterator_is_zero() {
+        let sum = fold_add(std::iter::empty::<Int8Gadget>()).unwrap();
+
+        assert_eq!(0, sum.value().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_multiset_equal() {
+        let cases: [(&[i8], &[i8]); 4] = [
+            (&[], &[]),
+            (&[5], &[5]),
+            (&[3, -1, 3, 0, -128, 3], &[-128, 3, 0, 3, 3, -1]),
+            (&[7, 7, 7], &[7, 7, 7]),
+        ];
+        for (a, b) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let a = Int8Gadget::new_witness_vec(cs.clone(), a).unwrap();
+            let b = Int8Gadget::new_witness_vec(cs.clone(), b).unwrap();
+
+            enforce_multiset_equal(&a, &b).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_multiset_equal_with_different_multiplicities_fails() {
+        let cases: [(&[i8], &[i8]); 3] = [
+            (&[3, -1, 3], &[3, -1, -1]),
+            (&[1, 2, 3], &[1, 2, 4]),
+            (&[0, 0], &[0, -128]),
+        ];
+        for (a, b) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let a = Int8Gadget::new_witness_vec(cs.clone(), a).unwrap();
+            let b = Int8Gadget::new_witness_vec(cs.clone(), b).unwrap();
+
+            enforce_multiset_equal(&a, &b).unwrap();
+
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_multiset_equal_with_different_lengths_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let a = Int8Gadget::new_witness_vec(cs.clone(), &[1, 1]).unwrap();
+        let b = Int8Gadget::new_witness_vec(cs, &[1]).unwrap();
+
+        assert!(enforce_multiset_equal(&a, &b).is_err());
+    }
+
+    #[test]
+    fn test_dot_product_mod() {
+        let cases: [(&[u8], &[u8], u8); 5] = [
+            (&[], &[], 13),
+            (&[3], &[4], 13),
+            (&[1, 2, 3, 4], &[5, 6, 7, 8], 251),
+            (&[255, 255, 255, 128], &[255, 254, 1, 200], 251),
+            (&[17, 0, 99, 200, 7], &[3, 250, 46, 200, 7], 3),
+        ];
+        for (a, b, modulus) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let a_bytes = Int8Gadget::new_witness_vec_from_u8(cs.clone(), a).unwrap();
+            let b_bytes = Int8Gadget::new_witness_vec_from_u8(cs.clone(), b).unwrap();
+            let expected = a
+                .iter()
+                .zip(b)
+                .map(|(a_byte, b_byte)| u32::from(*a_byte) * u32::from(*b_byte))
+                .sum::<u32>()
+                % u32::from(modulus);
+
+            let dot_product = dot_product_mod(&a_bytes, &b_bytes, modulus).unwrap();
+
+            assert_eq!(
+                expected,
+                u32::from(u8::from_ne_bytes(
+                    dot_product.value().unwrap().to_ne_bytes()
+                ))
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_dot_product_mod_with_different_lengths_fails() {
+        let a = [Int8Gadget::constant(1)];
+
+        assert!(dot_product_mod(&a, &[], 13).is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        for (cond, expected) in [(true, -42_i8), (false, 99_i8)] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let cond = Boolean::new_witness(cs.clone(), || Ok(cond)).unwrap();
+            let if_true = Int8Gadget::new_witness(cs.clone(), || Ok(-42)).unwrap();
+            let if_false = Int8Gadget::new_witness(cs.clone(), || Ok(99)).unwrap();
+
+            let selected = select(&cond, &if_true, &if_false).unwrap();
+
+            assert_eq!(expected, selected.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_canonical_le() {
+        // (little-endian bytes, is canonical)
+        let cases: [(&[i8], bool); 7] = [
+            (&[0], true),
+            (&[5], true),
+            (&[0, 1], true),
+            (&[-1, 0, -128], true),
+            (&[1, 0], false),
+            (&[0, 0], false),
+            (&[7, 1, 0], false),
+        ];
+        for (bytes, is_canonical) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec(cs.clone(), bytes).unwrap();
+
+            enforce_canonical_le(&bytes).unwrap();
+
+            assert_eq!(is_canonical, cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_canonical_le_of_an_empty_slice_fails() {
+        assert!(enforce_canonical_le::<ConstraintF>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_slice_parity() {
+        let cases: [&[i8]; 5] = [
+            &[],
+            &[0],
+            &[1],
+            &[-1, 0x0f, 0x10],
+            &[-128, 127, 85, 3, -86, 64],
+        ];
+        for values in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec(cs.clone(), values).unwrap();
+            let expected = values.iter().map(|value| value.count_ones()).sum::<u32>() % 2 == 1;
+
+            let parity = slice_parity(&bytes).unwrap();
+
+            assert_eq!(expected, parity.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_binary_search() {
+        let values = [-100_i8, -3, 0, 0, 7, 42, 42, 42, 127];
+        let targets = [-128_i8, -100, -50, -3, 0, 1, 7, 42, 100, 127];
+        for target in targets {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let sorted = Int8Gadget::new_witness_vec(cs.clone(), &values).unwrap();
+            let target_byte = Int8Gadget::new_witness(cs.clone(), || Ok(target)).unwrap();
+            let expected_index = values.partition_point(|value| *value < target);
+
+            let (index, found) = binary_search(&sorted, &target_byte).unwrap();
+
+            assert_eq!(
+                i8::try_from(expected_index).unwrap(),
+                index.value().unwrap()
+            );
+            assert_eq!(values.contains(&target), found.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_binary_search_over_an_unsorted_slice_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let unsorted = Int8Gadget::new_witness_vec(cs.clone(), &[1, 5, 3]).unwrap();
+        let target = Int8Gadget::new_witness(cs.clone(), || Ok(3)).unwrap();
+
+        let _ = binary_search(&unsorted, &target).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_prefix_sum() {
+        let values = [100_i8, 100, 100, -128, -128, -128, -128, 7, 0, 127];
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &values).unwrap();
+        let expected = values
+            .iter()
+            .scan(0_i16, |sum, value| {
+                *sum += i16::from(*value);
+                Some(*sum)
+            })
+            .collect::<Vec<i16>>();
+
+        let prefix_sums = prefix_sum(&bytes).unwrap();
+
+        assert_eq!(expected, prefix_sums.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_prefix_sum_of_the_most_negative_bytes_does_not_overflow() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &[i8::MIN; 256]).unwrap();
+
+        let prefix_sums = prefix_sum(&bytes).unwrap();
+
+        assert_eq!(i16::MIN, prefix_sums.last().unwrap().value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_first_difference_of_identical_slices() {
+        let values = [3_i8, -1, 0, 127, -128, 42];
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let a = Int8Gadget::new_witness_vec(cs.clone(), &values).unwrap();
+        let b = Int8Gadget::new_witness_vec(cs.clone(), &values).unwrap();
+
+        let (index, any_difference) = first_difference(&a, &b).unwrap();
+
+        assert_eq!(6, index.value().unwrap());
+        assert!(!any_difference.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_first_difference_at_various_positions() {
+        let a = [3_i8, -1, 0, 127, -128, 42];
+        let cases = [
+            ([4_i8, -1, 0, 127, -128, 42], 0_i8),
+            ([3, -2, 1, 0, 0, 0], 1),
+            ([3, -1, 0, 126, -128, 42], 3),
+            ([3, -1, 0, 127, -128, 43], 5),
+        ];
+        for (b, expected_index) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let a_bytes = Int8Gadget::new_witness_vec(cs.clone(), &a).unwrap();
+            let b_bytes = Int8Gadget::new_witness_vec(cs.clone(), &b).unwrap();
+
+            let (index, any_difference) = first_difference(&a_bytes, &b_bytes).unwrap();
+
+            assert_eq!(expected_index, index.value().unwrap());
+            assert!(any_difference.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_first_difference_with_different_lengths_fails() {
+        let a = [Int8Gadget::constant(1)];
+
+        assert!(first_difference(&a, &[]).is_err());
+    }
+
+    fn native_weighted_median(values: &[i8], weights: &[i8]) -> i8 {
+        let mut pairs = values.iter().zip(weights).collect::<Vec<_>>();
+        pairs.sort_by_key(|(value, _)| **value);
+        let total_weight = weights.iter().map(|weight| i16::from(*weight)).sum::<i16>();
+        let mut cumulative_weight = 0_i16;
+        for (value, weight) in pairs {
+            cumulative_weight += i16::from(*weight);
+            if 2 * cumulative_weight >= total_weight {
+                return *value;
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn test_weighted_median() {
+        let cases: [(&[i8], &[i8]); 5] = [
+            (&[5], &[3]),
+            (&[10, -20, 30], &[1, 1, 1]),
+            (&[10, -20, 30, 0], &[1, 1, 1, 1]),
+            (&[-128, 127, 3, 3, -7], &[100, 1, 50, 0, 49]),
+            (&[9, 8, 7, 6, 5, 4], &[0, 0, 0, 0, 0, 127]),
+        ];
+        for (values, weights) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let value_bytes = Int8Gadget::new_witness_vec(cs.clone(), values).unwrap();
+            let weight_bytes = Int8Gadget::new_witness_vec(cs.clone(), weights).unwrap();
+
+            let median = weighted_median(&value_bytes, &weight_bytes).unwrap();
+
+            assert_eq!(
+                native_weighted_median(values, weights),
+                median.value().unwrap()
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_weighted_median_with_a_negative_weight_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let values = Int8Gadget::new_witness_vec(cs.clone(), &[1, 2, 3]).unwrap();
+        let weights = Int8Gadget::new_witness_vec(cs.clone(), &[1, -1, 1]).unwrap();
+
+        weighted_median(&values, &weights).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_weighted_median_in_a_wrong_order_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let values = Int8Gadget::new_witness_vec(cs.clone(), &[10, -20, 30]).unwrap();
+        let weights = Int8Gadget::new_witness_vec(cs.clone(), &[1, 1, 1]).unwrap();
+        let unsorted_order = Int8Gadget::new_witness_vec(cs.clone(), &[0, 1, 2]).unwrap();
+
+        let median = weighted_median_in_order(&values, &weights, &unsorted_order).unwrap();
+
+        assert_eq!(-20, median.value().unwrap());
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_weighted_median_of_an_empty_slice_fails() {
+        assert!(weighted_median::<ConstraintF>(&[], &[]).is_err());
+    }
+}
@@ -0,0 +1,497 @@
+use super::{
+    helpers,
+    int16::Int16,
+    int32::Int32,
+    int8::Int8,
+    traits::{BitwiseOperationGadget, IntGadget},
+};
+use anyhow::{anyhow, ensure, Result};
+use ark_ff::Field;
+use ark_r1cs_std::{
+    prelude::{Boolean, EqGadget},
+    select::CondSelectGadget,
+};
+
+/// Returns whether `digits` pass the Luhn checksum, the last digit being the
+/// check digit. Digits may be given either as numbers (`0..=9`) or as ASCII
+/// characters (`'0'..='9'`); any other byte makes the result false.
+pub fn luhn_valid<F: Field>(digits: &[Int8<F>]) -> Result<Boolean<F>> {
+    ensure!(
+        !digits.is_empty(),
+        "Cannot validate an empty digit sequence"
+    );
+    let ascii_zero = Int8::constant(48);
+    let minus_ascii_zero = Int8::constant(-48);
+    let nine = Int8::constant(9);
+    let minus_nine = Int8::constant(-9);
+    let ten = Int8::constant(10);
+
+    let mut all_digits_are_valid = Boolean::TRUE;
+    let mut contributions = Vec::with_capacity(digits.len());
+    for (position, digit) in digits.iter().rev().enumerate() {
+        let is_ascii = digit.is_lt(&ascii_zero)?.not();
+        let numeric = Int8::conditionally_select(
+            &is_ascii,
+            &Int8::addmany(&[digit.clone(), minus_ascii_zero.clone()])?,
+            digit,
+        )?;
+        let is_digit = numeric.is_lt(&ten)?.and(&numeric.sign()?.not())?;
+        all_digits_are_valid = all_digits_are_valid.and(&is_digit)?;
+        // Invalid digits are zeroed so the sum stays non-negative.
+        let numeric = Int8::conditionally_select(&is_digit, &numeric, &Int8::constant(0))?;
+
+        // Every second digit, starting from the one next to the check digit,
+        // is doubled and its digits summed, i.e. 9 is subtracted if above 9.
+        let contribution = if position % 2 == 1 {
+            // Doubling a digit is a constraint free shift as it is below 10.
+            let mut doubled_bits = vec![Boolean::FALSE];
+            doubled_bits.extend_from_slice(&numeric.bits[..7]);
+            let doubled = Int8::from_bits_le(&doubled_bits)?;
+            Int8::conditionally_select(
+                &nine.is_lt(&doubled)?,
+                &Int8::addmany(&[doubled.clone(), minus_nine.clone()])?,
+                &doubled,
+            )?
+        } else {
+            numeric
+        };
+        contributions.push(Int32::from_bits_le(&helpers::sign_extend(
+            &contribution.bits,
+            32,
+        )?)?);
+    }
+
+    let sum = Int32::addmany(&contributions)?;
+    let (_, remainder) = helpers::unsigned_div_rem_constant(&sum.bits, 10)?;
+    Ok(all_digits_are_valid.and(&Int8::from_bits_le(&remainder)?.is_zero()?)?)
+}
+
+/// Sums the numeric values of ASCII digits (`'0'..='9'`) into an Int16,
+/// together with whether every byte is a digit. Bytes that are not digits
+/// count as zero.
+pub fn digit_sum<F: Field>(digits: &[Int8<F>]) -> Result<(Int16<F>, Boolean<F>)> {
+    // 9 * 3640 is the largest such sum that fits in an i16.
+    ensure!(
+        digits.len() <= 3640,
+        "Too many digits, the sum could overflow an Int16"
+    );
+    let minus_ascii_zero = Int8::constant(-48);
+
+    let mut all_digits_are_valid = Boolean::TRUE;
+    let mut values = vec![Int16::constant(0)];
+    for digit in digits {
+        let is_digit = digit.is_in_range(48, 57)?;
+        all_digits_are_valid = all_digits_are_valid.and(&is_digit)?;
+        let numeric = Int8::conditionally_select(
+            &is_digit,
+            &Int8::addmany(&[digit.clone(), minus_ascii_zero.clone()])?,
+            &Int8::constant(0),
+        )?;
+        values.push(Int16::from_bits_le(&helpers::sign_extend(
+            &numeric.bits,
+            16,
+        )?)?);
+    }
+
+    Ok((Int16::addmany(&values)?, all_digits_are_valid))
+}
+
+/// Computes the Fletcher-16 checksum of `bytes`, read as unsigned, as the
+/// Int16 `(sum2 << 8) | sum1` where `sum1` is the running sum of the bytes
+/// and `sum2` the running sum of `sum1`, both modulo 255.
+pub fn fletcher16<F: Field>(bytes: &[Int8<F>]) -> Result<Int16<F>> {
+    let mut sum1 = vec![Boolean::FALSE; 8];
+    let mut sum2 = vec![Boolean::FALSE; 8];
+    for byte in bytes {
+        // Both sums stay below 255, so adding a byte fits in 9 bits.
+        let (partial_sum, carry) = helpers::ripple_carry_add(&sum1, byte.bits(), Boolean::FALSE)?;
+        (_, sum1) = helpers::unsigned_div_rem_constant(&[partial_sum, vec![carry]].concat(), 255)?;
+        let (partial_sum, carry) = helpers::ripple_carry_add(&sum2, &sum1, Boolean::FALSE)?;
+        (_, sum2) = helpers::unsigned_div_rem_constant(&[partial_sum, vec![carry]].concat(), 255)?;
+    }
+    Int16::from_bits_le(&[sum1, sum2].concat())
+}
+
+/// Toy keyed MAC over `message`. Starting from `key`, every byte is folded
+/// into the digest by rotating it left one bit, XORing the byte and adding
+/// the key (wrapping). It is not cryptographically secure.
+pub fn keyed_mac<F: Field>(message: &[Int8<F>], key: &Int8<F>) -> Result<Int8<F>> {
+    let mut digest = key.clone();
+    for byte in message {
+        let mixed = digest.rotate_left_free(1)?.xor(byte)?;
+        digest = Int8::addmany(&[mixed, key.clone()])?;
+    }
+    Ok(digest)
+}
+
+/// Enforces that `tag` is the `keyed_mac` of `message` under `key`.
+pub fn enforce_keyed_mac<F: Field>(
+    message: &[Int8<F>],
+    key: &Int8<F>,
+    tag: &Int8<F>,
+) -> Result<()> {
+    keyed_mac(message, key)?.enforce_equal(tag)?;
+    Ok(())
+}
+
+/// Enforces that `parity` is the XOR of all the `data` bytes, as in RAID 5,
+/// so any single erased byte can be recovered from the others.
+pub fn enforce_xor_parity<F: Field>(data: &[Int8<F>], parity: &Int8<F>) -> Result<()> {
+    data.iter()
+        .try_fold(Int8::constant(0), |acc, byte| acc.xor(byte))?
+        .enforce_equal(parity)?;
+    Ok(())
+}
+
+/// Computes `(multiplicand * multiplier + addend) mod modulus` over unsigned
+/// little-endian bits, returning the 8 bits of the result.
+fn mul_add_mod<F: Field>(
+    multiplicand: &[Boolean<F>],
+    multiplier: u8,
+    addend: &[Boolean<F>],
+    modulus: u8,
+) -> Result<Vec<Boolean<F>>> {
+    // Wide enough for the product plus a carry out of the addition.
+    let width = (multiplicand.len() + 8).max(addend.len()) + 1;
+    let mut sum = addend.to_vec();
+    sum.resize(width, Boolean::FALSE);
+    for i in 0..8 {
+        if (multiplier >> i) & 1 == 1 {
+            let mut shifted_multiplicand = vec![Boolean::FALSE; i];
+            shifted_multiplicand.extend(multiplicand.iter().cloned());
+            shifted_multiplicand.resize(width, Boolean::FALSE);
+            (sum, _) = helpers::ripple_carry_add(&sum, &shifted_multiplicand, Boolean::FALSE)?;
+        }
+    }
+    let (_, remainder) = helpers::unsigned_div_rem_constant(&sum, modulus)?;
+    Ok(remainder)
+}
+
+/// Computes the polynomial hash `sum(bytes[i] * base^i) mod modulus` of
+/// `bytes`, read as unsigned, with Horner's method so every intermediate
+/// value is reduced. Errors if `modulus` is zero.
+pub fn poly_hash<F: Field>(bytes: &[Int8<F>], base: u8, modulus: u8) -> Result<Int8<F>> {
+    ensure!(modulus != 0, "The modulus can't be zero");
+    let mut hash = vec![Boolean::FALSE; 8];
+    // Starting from the last byte leaves `bytes[i]` multiplied by `base^i`.
+    for byte in bytes.iter().rev() {
+        hash = mul_add_mod(&hash, base, &byte.bits, modulus)?;
+    }
+    Int8::from_bits_le(&hash)
+}
+
+/// Slides the window hashed by `poly_hash` one byte forward: `prev` is the
+/// hash of a window starting with `out_byte` and the result is the hash of
+/// the window without it and followed by `in_byte`. `base_pow` must be
+/// `base^(window_len - 1) mod modulus`. Errors if `base` has no inverse
+/// modulo `modulus`.
+pub fn roll_hash<F: Field>(
+    prev: &Int8<F>,
+    out_byte: &Int8<F>,
+    in_byte: &Int8<F>,
+    base: u8,
+    base_pow: u8,
+    modulus: u8,
+) -> Result<Int8<F>> {
+    ensure!(modulus != 0, "The modulus can't be zero");
+    let modulus_u16 = u16::from(modulus);
+    let base_inverse = (0..modulus)
+        .find(|candidate| {
+            (u16::from(base) * u16::from(*candidate)) % modulus_u16 == 1 % modulus_u16
+        })
+        .ok_or_else(|| anyhow!("{base} has no inverse modulo {modulus}"))?;
+
+    // `-out_byte` is `out_byte * (modulus - 1)`, which keeps everything
+    // unsigned.
+    let minus_out_byte = mul_add_mod(&out_byte.bits, modulus - 1, &[], modulus)?;
+    let (difference, carry) =
+        helpers::ripple_carry_add(&prev.bits, &minus_out_byte, Boolean::FALSE)?;
+    let in_term = mul_add_mod(&in_byte.bits, base_pow, &[], modulus)?;
+    // Dividing by the base lowers the power of every remaining byte by one.
+    let hash = mul_add_mod(
+        &[difference, vec![carry]].concat(),
+        base_inverse,
+        &in_term,
+        modulus,
+    )?;
+    Int8::from_bits_le(&hash)
+}
+
+#[cfg(test)]
+mod checksum_tests {
+    use super::{
+        digit_sum, enforce_keyed_mac, enforce_xor_parity, fletcher16, luhn_valid, poly_hash,
+        roll_hash,
+    };
+    use crate::gadgets::{ConstraintF, Int8Gadget};
+    use ark_r1cs_std::{prelude::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_luhn_valid_with_a_valid_ascii_number() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let digits = Int8Gadget::new_witness_vec_from_u8(cs.clone(), b"79927398713").unwrap();
+
+        let is_valid = luhn_valid(&digits).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert!(is_valid.value().unwrap());
+    }
+
+    #[test]
+    fn test_luhn_valid_with_a_valid_numeric_number() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let digits = Int8Gadget::new_witness_vec(
+            cs.clone(),
+            &[4_i8, 5, 3, 9, 1, 4, 8, 8, 0, 3, 4, 3, 6, 4, 6, 7],
+        )
+        .unwrap();
+
+        let is_valid = luhn_valid(&digits).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert!(is_valid.value().unwrap());
+    }
+
+    #[test]
+    fn test_luhn_valid_with_a_wrong_check_digit() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let digits = Int8Gadget::new_witness_vec_from_u8(cs.clone(), b"79927398710").unwrap();
+
+        let is_valid = luhn_valid(&digits).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert!(!is_valid.value().unwrap());
+    }
+
+    #[test]
+    fn test_luhn_valid_with_a_non_digit() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let digits = Int8Gadget::new_witness_vec_from_u8(cs.clone(), b"7992739871a").unwrap();
+
+        let is_valid = luhn_valid(&digits).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert!(!is_valid.value().unwrap());
+    }
+
+    #[test]
+    fn test_digit_sum() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let digits = Int8Gadget::new_witness_vec_from_u8(cs.clone(), b"12345").unwrap();
+
+        let (sum, is_valid) = digit_sum(&digits).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert!(is_valid.value().unwrap());
+        assert_eq!(15, sum.value().unwrap());
+    }
+
+    #[test]
+    fn test_digit_sum_with_invalid_digits() {
+        for text in [
+            b"12a45".as_slice(),
+            b"9/".as_slice(),
+            b"0:".as_slice(),
+            b"\x01".as_slice(),
+        ] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let digits = Int8Gadget::new_witness_vec_from_u8(cs.clone(), text).unwrap();
+
+            let (_, is_valid) = digit_sum(&digits).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert!(!is_valid.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_digit_sum_with_too_many_digits_fails() {
+        let nines = vec![Int8Gadget::constant(57); 3641];
+
+        assert!(digit_sum(&nines).is_err());
+        assert_eq!(
+            9_i16 * 3640_i16,
+            digit_sum(nines.get(1..).unwrap())
+                .unwrap()
+                .0
+                .value()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fletcher16() {
+        let cases = [
+            (b"abcde".as_slice(), 0xc8f0_u16),
+            (b"abcdef".as_slice(), 0x2057),
+            (b"abcdefgh".as_slice(), 0x0627),
+            (b"\xff\xff\x01".as_slice(), 0x0101),
+            (b"".as_slice(), 0x0000),
+        ];
+        for (text, expected) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec_from_u8(cs.clone(), text).unwrap();
+
+            let checksum = fletcher16(&bytes).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(
+                i16::from_ne_bytes(expected.to_ne_bytes()),
+                checksum.value().unwrap()
+            );
+        }
+    }
+
+    fn native_keyed_mac(message: &[u8], key: i8) -> i8 {
+        message.iter().fold(key, |digest, byte| {
+            let mixed = digest.rotate_left(1) ^ i8::from_ne_bytes([*byte]);
+            mixed.wrapping_add(key)
+        })
+    }
+
+    #[test]
+    fn test_enforce_keyed_mac_with_a_valid_tag() {
+        let message = b"attack at dawn";
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let message_var = Int8Gadget::new_witness_vec_from_u8(cs.clone(), message).unwrap();
+        let key = Int8Gadget::new_witness(cs.clone(), || Ok(-77_i8)).unwrap();
+        let tag = Int8Gadget::new_input(cs.clone(), || Ok(native_keyed_mac(message, -77))).unwrap();
+
+        enforce_keyed_mac(&message_var, &key, &tag).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_keyed_mac_with_a_forged_tag_is_not_satisfied() {
+        let message = b"attack at dawn";
+        let cases = [
+            (
+                b"attack at dawn".as_slice(),
+                12_i8,
+                native_keyed_mac(message, -77),
+            ),
+            (
+                b"attack at dusk".as_slice(),
+                -77,
+                native_keyed_mac(message, -77),
+            ),
+            (
+                message.as_slice(),
+                -77,
+                native_keyed_mac(message, -77).wrapping_add(1),
+            ),
+        ];
+        for (forged_message, key, tag) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let message_var =
+                Int8Gadget::new_witness_vec_from_u8(cs.clone(), forged_message).unwrap();
+            let key = Int8Gadget::new_witness(cs.clone(), || Ok(key)).unwrap();
+            let tag = Int8Gadget::new_input(cs.clone(), || Ok(tag)).unwrap();
+
+            enforce_keyed_mac(&message_var, &key, &tag).unwrap();
+
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_xor_parity() {
+        let data = [0x12_i8, -3, 0x7f, 0, -128];
+        let parity = data.iter().fold(0_i8, |acc, byte| acc ^ byte);
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let data = Int8Gadget::new_witness_vec(cs.clone(), &data).unwrap();
+        let parity = Int8Gadget::new_witness(cs.clone(), || Ok(parity)).unwrap();
+
+        enforce_xor_parity(&data, &parity).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_xor_parity_with_corrupted_byte_fails() {
+        let data = [0x12_i8, -3, 0x7f, 0, -128];
+        let parity = data.iter().fold(0_i8, |acc, byte| acc ^ byte);
+        let corrupted_data = [0x12_i8, -3, 0x7e, 0, -128];
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let corrupted_data = Int8Gadget::new_witness_vec(cs.clone(), &corrupted_data).unwrap();
+        let parity = Int8Gadget::new_witness(cs.clone(), || Ok(parity)).unwrap();
+
+        enforce_xor_parity(&corrupted_data, &parity).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    fn native_poly_hash(bytes: &[u8], base: u8, modulus: u8) -> u8 {
+        let hash = bytes.iter().rev().fold(0_u32, |hash, byte| {
+            (hash * u32::from(base) + u32::from(*byte)) % u32::from(modulus)
+        });
+        u8::try_from(hash).unwrap()
+    }
+
+    #[test]
+    fn test_poly_hash() {
+        let cases: [(&[u8], u8, u8); 6] = [
+            (b"", 31, 251),
+            (b"a", 31, 251),
+            (b"hello world", 31, 251),
+            (b"hello world", 255, 255),
+            (b"\xff\xfe\x00\x80", 2, 7),
+            (b"rolling", 0, 100),
+        ];
+        for (text, base, modulus) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec_from_u8(cs.clone(), text).unwrap();
+
+            let hash = poly_hash(&bytes, base, modulus).unwrap();
+
+            assert_eq!(
+                native_poly_hash(text, base, modulus),
+                u8::from_ne_bytes(hash.value().unwrap().to_ne_bytes())
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_poly_hash_with_zero_modulus_fails() {
+        assert!(poly_hash::<ConstraintF>(&[], 31, 0).is_err());
+    }
+
+    #[test]
+    fn test_roll_hash_matches_poly_hash_of_the_slid_window() {
+        let (base, modulus, window_len) = (31_u8, 251_u8, 4_usize);
+        let base_pow = (1..window_len).fold(1_u8, |power, _| {
+            u8::try_from(u32::from(power) * u32::from(base) % u32::from(modulus)).unwrap()
+        });
+        let text = b"\xffhello, rolling world\x80";
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bytes = Int8Gadget::new_witness_vec_from_u8(cs.clone(), text).unwrap();
+        let windows = bytes.windows(window_len).collect::<Vec<_>>();
+
+        let mut hash = poly_hash(windows.first().unwrap(), base, modulus).unwrap();
+        for (window, next_window) in windows.iter().zip(windows.iter().skip(1)) {
+            hash = roll_hash(
+                &hash,
+                window.first().unwrap(),
+                next_window.last().unwrap(),
+                base,
+                base_pow,
+                modulus,
+            )
+            .unwrap();
+
+            let expected_hash = poly_hash(next_window, base, modulus).unwrap();
+            assert_eq!(expected_hash.value().unwrap(), hash.value().unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_roll_hash_with_non_invertible_base_fails() {
+        let byte = Int8Gadget::constant(1);
+
+        assert!(roll_hash(&byte, &byte, &byte, 6, 36, 9).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+use super::{
+    int8::Int8,
+    lookup,
+    traits::{BitwiseOperationGadget, IntGadget},
+};
+use anyhow::{anyhow, Result};
+use ark_ff::Field;
+use ark_r1cs_std::{
+    prelude::{AllocVar, Boolean, EqGadget},
+    R1CSVar,
+};
+use ark_relations::r1cs::SynthesisError;
+
+/// Enforces the one-time pad relation `plaintext ^ key == ciphertext`.
+pub fn enforce_otp<F: Field>(
+    plaintext: &Int8<F>,
+    key: &Int8<F>,
+    ciphertext: &Int8<F>,
+) -> Result<()> {
+    plaintext.xor(key)?.enforce_equal(ciphertext)?;
+    Ok(())
+}
+
+/// Round function of `feistel_round`: adds the round key (wrapping) and
+/// rotates the value left by 3 bits.
+fn feistel_function<F: Field>(half: &Int8<F>, round_key: &Int8<F>) -> Result<Int8<F>> {
+    Int8::addmany(&[half.clone(), round_key.clone()])?.rotate_left_free(3)
+}
+
+/// One round of a Feistel network over a pair of bytes: returns
+/// `(right, left ^ f(right, round_key))`, where `f` adds the round key and
+/// rotates left by 3 bits. A round is undone by running it on the swapped
+/// output and swapping the result back.
+pub fn feistel_round<F: Field>(
+    left: &Int8<F>,
+    right: &Int8<F>,
+    round_key: &Int8<F>,
+) -> Result<(Int8<F>, Int8<F>)> {
+    let new_right = left.xor(&feistel_function(right, round_key)?)?;
+    Ok((right.clone(), new_right))
+}
+
+/// The AES S-box, mapping every byte to its SubBytes substitution.
+const AES_SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// Enforces that `output` is the AES SubBytes substitution of `input`, by
+/// looking `input` up in the constant S-box.
+pub fn enforce_aes_sbox<F: Field>(input: &Int8<F>, output: &Int8<F>) -> Result<()> {
+    let table = AES_SBOX.map(|entry| i8::from_ne_bytes([entry]));
+    lookup::byte_lookup(input, &table)?.enforce_equal(output)?;
+    Ok(())
+}
+
+/// Multiplies two GF(2^8) elements natively, for computing witnesses.
+fn native_gf256_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1_u32) ^ if a & 0x80 == 0 { 0 } else { 0x1b };
+        b >>= 1_u32;
+    }
+    product
+}
+
+/// Multiplies the little-endian bits of a GF(2^8) element by `x`: shifts
+/// them left and, if the top bit overflows, reduces by XORing `0x1b`.
+fn xtime<F: Field>(bits: &[Boolean<F>]) -> Result<Vec<Boolean<F>>> {
+    let (overflow, low_bits) = bits
+        .split_last()
+        .ok_or_else(|| anyhow!("Error getting the top bit"))?;
+    let mut shifted = vec![Boolean::FALSE];
+    shifted.extend(low_bits.iter().cloned());
+    for i in [0_usize, 1, 3, 4] {
+        let bit = shifted
+            .get_mut(i)
+            .ok_or_else(|| anyhow!("Error getting bit {i}"))?;
+        *bit = bit.xor(overflow)?;
+    }
+    Ok(shifted)
+}
+
+impl<F: Field> Int8<F> {
+    /// Multiplies the bytes as elements of GF(2^8) reduced by the AES
+    /// polynomial `x^8 + x^4 + x^3 + x + 1` (0x11b).
+    pub fn gf256_mul(&self, other: &Self) -> Result<Self> {
+        let mut product = vec![Boolean::FALSE; 8];
+        let mut multiple = self.bits.to_vec();
+        for (i, other_bit) in other.bits.iter().enumerate() {
+            for (product_bit, multiple_bit) in product.iter_mut().zip(&multiple) {
+                *product_bit = product_bit.xor(&multiple_bit.and(other_bit)?)?;
+            }
+            if i < 7 {
+                multiple = xtime(&multiple)?;
+            }
+        }
+        Self::from_bits_le(&product)
+    }
+
+    /// Returns the multiplicative inverse of the byte in GF(2^8) and whether
+    /// it exists, which is the case iff the byte is non zero. When it doesn't
+    /// exist the returned inverse is zero.
+    ///
+    /// The prover supplies the inverse and we enforce that its product with
+    /// the byte is one, or that it is zero for a zero byte.
+    pub fn gf256_inverse(&self) -> Result<(Self, Boolean<F>)> {
+        let is_valid = self.is_zero()?.not();
+        let inverse_value = || -> Result<i8, SynthesisError> {
+            let value = u8::from_ne_bytes(self.value()?.to_ne_bytes());
+            let inverse = (1_u8..=255)
+                .find(|candidate| native_gf256_mul(value, *candidate) == 1)
+                .unwrap_or(0);
+            Ok(i8::from_ne_bytes([inverse]))
+        };
+
+        let cs = self.cs();
+        if cs.is_none() {
+            return Ok((Self::constant(inverse_value()?), is_valid));
+        }
+
+        let inverse = Self::new_witness(cs, inverse_value)?;
+        let mut expected_product = vec![is_valid.clone()];
+        expected_product.resize(8, Boolean::FALSE);
+        self.gf256_mul(&inverse)?
+            .enforce_equal(&Self::from_bits_le(&expected_product)?)?;
+        inverse.conditional_enforce_equal(&Self::constant(0), &is_valid.not())?;
+
+        Ok((inverse, is_valid))
+    }
+}
+
+/// Applies the AES MixColumns step to a state column, i.e. multiplies it in
+/// GF(2^8) by the circulant matrix with rows `[2, 3, 1, 1]` rotated.
+///
+/// With `t` the XOR of the whole column, every output byte is
+/// `a[i] ^ t ^ 2 * (a[i] ^ a[i + 1])`, so only XORs and doublings are needed.
+pub fn aes_mix_column<F: Field>(col: &[Int8<F>; 4]) -> Result<[Int8<F>; 4]> {
+    let [a0, a1, a2, a3] = col;
+    let column_xor = a0.xor(a1)?.xor(a2)?.xor(a3)?;
+    let mut mixed = Vec::with_capacity(4);
+    for (byte, next_byte) in col.iter().zip(col.iter().cycle().skip(1)) {
+        let doubled = Int8::from_bits_le(&xtime(byte.xor(next_byte)?.bits())?)?;
+        mixed.push(byte.xor(&column_xor)?.xor(&doubled)?);
+    }
+    mixed
+        .try_into()
+        .map_err(|_e| anyhow!("Error building the mixed column"))
+}
+
+#[cfg(test)]
+mod cipher_tests {
+    use super::{aes_mix_column, enforce_aes_sbox, enforce_otp, feistel_round, native_gf256_mul};
+    use crate::gadgets::{ConstraintF, Int8Gadget};
+    use ark_r1cs_std::{prelude::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_enforce_otp() {
+        let (plaintext, key) = (0x48_i8, -0x5b_i8);
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let plaintext_byte = Int8Gadget::new_witness(cs.clone(), || Ok(plaintext)).unwrap();
+        let key_byte = Int8Gadget::new_witness(cs.clone(), || Ok(key)).unwrap();
+        let ciphertext_byte = Int8Gadget::new_witness(cs.clone(), || Ok(plaintext ^ key)).unwrap();
+
+        enforce_otp(&plaintext_byte, &key_byte, &ciphertext_byte).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_otp_with_a_corrupted_ciphertext_is_not_satisfied() {
+        let (plaintext, key) = (0x48_i8, -0x5b_i8);
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let plaintext_byte = Int8Gadget::new_witness(cs.clone(), || Ok(plaintext)).unwrap();
+        let key_byte = Int8Gadget::new_witness(cs.clone(), || Ok(key)).unwrap();
+        let corrupted_ciphertext_byte =
+            Int8Gadget::new_witness(cs.clone(), || Ok((plaintext ^ key) ^ 0x04)).unwrap();
+
+        enforce_otp(&plaintext_byte, &key_byte, &corrupted_ciphertext_byte).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_feistel_round() {
+        let (left, right, round_key) = (0x12_i8, -0x66_i8, 0x5a_i8);
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let left_byte = Int8Gadget::new_witness(cs.clone(), || Ok(left)).unwrap();
+        let right_byte = Int8Gadget::new_witness(cs.clone(), || Ok(right)).unwrap();
+        let round_key_byte = Int8Gadget::new_witness(cs.clone(), || Ok(round_key)).unwrap();
+
+        let (new_left, new_right) =
+            feistel_round(&left_byte, &right_byte, &round_key_byte).unwrap();
+
+        assert_eq!(right, new_left.value().unwrap());
+        assert_eq!(
+            left ^ right.wrapping_add(round_key).rotate_left(3),
+            new_right.value().unwrap()
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_feistel_round_inverse_recovers_the_inputs() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let left = Int8Gadget::new_witness(cs.clone(), || Ok(-128)).unwrap();
+        let right = Int8Gadget::new_witness(cs.clone(), || Ok(77)).unwrap();
+        let round_key = Int8Gadget::new_witness(cs.clone(), || Ok(-3)).unwrap();
+
+        let (new_left, new_right) = feistel_round(&left, &right, &round_key).unwrap();
+        let (recovered_right, recovered_left) =
+            feistel_round(&new_right, &new_left, &round_key).unwrap();
+
+        assert_eq!(left.value().unwrap(), recovered_left.value().unwrap());
+        assert_eq!(right.value().unwrap(), recovered_right.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_aes_sbox() {
+        for (input, output) in [(0x00_u8, 0x63_u8), (0x01, 0x7c), (0x53, 0xed), (0xff, 0x16)] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let input =
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([input]))).unwrap();
+            let output =
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([output]))).unwrap();
+
+            enforce_aes_sbox(&input, &output).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_aes_sbox_with_a_wrong_output_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let input = Int8Gadget::new_witness(cs.clone(), || Ok(0x53)).unwrap();
+        let wrong_output = Int8Gadget::new_witness(cs.clone(), || Ok(0x63)).unwrap();
+
+        enforce_aes_sbox(&input, &wrong_output).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_gf256_mul() {
+        // (a, b, a * b) from FIPS-197 and the AES inverse of 0x53.
+        let known_products = [
+            (0x57_u8, 0x83_u8, 0xc1_u8),
+            (0x57, 0x13, 0xfe),
+            (0x57, 0x02, 0xae),
+            (0x53, 0xca, 0x01),
+            (0xff, 0x01, 0xff),
+            (0xff, 0x00, 0x00),
+        ];
+        for (a, b, expected) in known_products {
+            assert_eq!(expected, native_gf256_mul(a, b));
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let a = Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([a]))).unwrap();
+            let b = Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([b]))).unwrap();
+
+            let product = a.gf256_mul(&b).unwrap();
+
+            assert_eq!(expected, product.value().unwrap().to_ne_bytes()[0]);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_gf256_mul_matches_the_multiplication_table() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        for a in (0_u8..=255).step_by(17) {
+            for b in (0_u8..=255).step_by(13) {
+                let a_byte =
+                    Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([a]))).unwrap();
+                let b_byte =
+                    Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([b]))).unwrap();
+
+                let product = a_byte.gf256_mul(&b_byte).unwrap();
+
+                assert_eq!(
+                    native_gf256_mul(a, b),
+                    product.value().unwrap().to_ne_bytes()[0]
+                );
+            }
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_gf256_inverse_of_non_zero_bytes() {
+        for value in [0x01_u8, 0x02, 0x53, 0xca, 0x57, 0x80, 0xff] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte =
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([value]))).unwrap();
+
+            let (inverse, is_valid) = byte.gf256_inverse().unwrap();
+
+            let inverse = inverse.value().unwrap().to_ne_bytes()[0];
+            assert_eq!(1, native_gf256_mul(value, inverse));
+            assert!(is_valid.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_gf256_inverse_of_zero() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let byte = Int8Gadget::new_witness(cs.clone(), || Ok(0)).unwrap();
+
+        let (inverse, is_valid) = byte.gf256_inverse().unwrap();
+
+        assert_eq!(0, inverse.value().unwrap());
+        assert!(!is_valid.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_aes_mix_column() {
+        // Test vectors from the AES MixColumns Wikipedia article.
+        let vectors = [
+            ([0xdb_u8, 0x13, 0x53, 0x45], [0x8e_u8, 0x4d, 0xa1, 0xbc]),
+            ([0xf2, 0x0a, 0x22, 0x5c], [0x9f, 0xdc, 0x58, 0x9d]),
+            ([0x01, 0x01, 0x01, 0x01], [0x01, 0x01, 0x01, 0x01]),
+            ([0xc6, 0xc6, 0xc6, 0xc6], [0xc6, 0xc6, 0xc6, 0xc6]),
+            ([0xd4, 0xd4, 0xd4, 0xd5], [0xd5, 0xd5, 0xd7, 0xd6]),
+            ([0x2d, 0x26, 0x31, 0x4c], [0x4d, 0x7e, 0xbd, 0xf8]),
+        ];
+        for (column, expected) in vectors {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let column = column.map(|byte| {
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([byte]))).unwrap()
+            });
+
+            let mixed = aes_mix_column(&column).unwrap();
+
+            assert_eq!(
+                expected,
+                mixed.map(|byte| byte.value().unwrap().to_ne_bytes()[0])
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+}
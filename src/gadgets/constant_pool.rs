@@ -0,0 +1,72 @@
+use super::int8::Int8;
+use ark_ff::Field;
+use std::collections::HashMap;
+
+/// Caches constant bytes by value, so circuits using the same constant many
+/// times build its bits only once and hand out clones afterwards.
+#[derive(Clone, Debug, Default)]
+pub struct ConstantPool<F: Field> {
+    constants: HashMap<i8, Int8<F>>,
+}
+
+impl<F: Field> ConstantPool<F> {
+    pub fn new() -> Self {
+        Self {
+            constants: HashMap::new(),
+        }
+    }
+
+    /// Returns the constant byte for `value`, building it on first use.
+    pub fn get(&mut self, value: i8) -> Int8<F> {
+        self.constants
+            .entry(value)
+            .or_insert_with(|| Int8::constant(value))
+            .clone()
+    }
+
+    /// Number of distinct constants built so far.
+    pub fn len(&self) -> usize {
+        self.constants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constants.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod constant_pool_tests {
+    use super::ConstantPool;
+    use crate::gadgets::ConstraintF;
+    use ark_r1cs_std::{R1CSVar, ToBitsGadget};
+
+    #[test]
+    fn test_repeated_get_returns_equivalent_constants() {
+        let mut pool = ConstantPool::<ConstraintF>::new();
+
+        let first = pool.get(5);
+        let second = pool.get(5);
+
+        assert_eq!(1, pool.len());
+        assert_eq!(5, first.value().unwrap());
+        assert_eq!(first.value().unwrap(), second.value().unwrap());
+        assert!(first.is_constant() && second.is_constant());
+        let first_bits = first.to_bits_le().unwrap();
+        let second_bits = second.to_bits_le().unwrap();
+        for (first_bit, second_bit) in first_bits.iter().zip(&second_bits) {
+            assert_eq!(first_bit.value().unwrap(), second_bit.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_get_builds_one_constant_per_value() {
+        let mut pool = ConstantPool::<ConstraintF>::new();
+        assert!(pool.is_empty());
+
+        for value in [-128_i8, 0, 127, 0, -128] {
+            assert_eq!(value, pool.get(value).value().unwrap());
+        }
+
+        assert_eq!(3, pool.len());
+    }
+}
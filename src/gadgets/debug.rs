@@ -0,0 +1,244 @@
+use super::{
+    int8::Int8,
+    traits::{ArithmeticGadget, BitwiseOperationGadget},
+};
+use anyhow::{anyhow, Result};
+use ark_ff::Field;
+use ark_r1cs_std::R1CSVar;
+use ark_relations::r1cs::ConstraintSystemRef;
+use std::{cell::Cell, hash::Hasher, rc::Rc};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// 64-bit FNV-1a. Used instead of `DefaultHasher` because the latter is not
+/// guaranteed to produce the same output across Rust releases.
+struct FnvHasher(u64);
+
+impl FnvHasher {
+    /// Hashes `count` as 8 little-endian bytes, so the hash doesn't depend on
+    /// the width or endianness of `usize`.
+    fn write_count(&mut self, count: usize) -> Result<()> {
+        self.write(&u64::try_from(count)?.to_le_bytes());
+        Ok(())
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+/// Computes a stable hash of the constraint matrices of `cs`. Only the shape
+/// of the circuit is hashed (not the assignment), so tests can assert that a
+/// refactor did not add, remove or change any constraint.
+///
+/// Building the matrices requires finalizing the constraint system, which
+/// inlines its linear combinations, so this is done on a copy and `cs` is
+/// left untouched.
+pub fn constraint_fingerprint<F: Field>(cs: &ConstraintSystemRef<F>) -> Result<u64> {
+    let cs_copy = ConstraintSystemRef::new(
+        cs.borrow()
+            .ok_or_else(|| anyhow!("Cannot fingerprint a missing constraint system"))?
+            .clone(),
+    );
+    cs_copy.finalize();
+    let matrices = cs_copy
+        .to_matrices()
+        .ok_or_else(|| anyhow!("Error building the constraint matrices"))?;
+
+    let mut hasher = FnvHasher(FNV_OFFSET_BASIS);
+    hasher.write_count(matrices.num_instance_variables)?;
+    hasher.write_count(matrices.num_witness_variables)?;
+    hasher.write_count(matrices.num_constraints)?;
+    for matrix in [&matrices.a, &matrices.b, &matrices.c] {
+        hasher.write_count(matrix.len())?;
+        for row in matrix {
+            hasher.write_count(row.len())?;
+            for (coefficient, index) in row {
+                let mut coefficient_bytes = Vec::new();
+                coefficient
+                    .serialize(&mut coefficient_bytes)
+                    .map_err(|e| anyhow!("Error serializing a coefficient: {e:?}"))?;
+                hasher.write(&coefficient_bytes);
+                hasher.write_count(*index)?;
+            }
+        }
+    }
+    Ok(hasher.finish())
+}
+
+/// An `Int8` that adds the number of constraints of every operation on it to
+/// a counter, shared with every value derived from it, to profile circuits
+/// without touching the gadgets themselves.
+#[derive(Clone, Debug)]
+pub struct CountingInt8<F: Field> {
+    inner: Int8<F>,
+    counter: Rc<Cell<usize>>,
+}
+
+impl<F: Field> CountingInt8<F> {
+    pub fn new(inner: Int8<F>, counter: Rc<Cell<usize>>) -> Self {
+        Self { inner, counter }
+    }
+
+    pub fn inner(&self) -> &Int8<F> {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> Int8<F> {
+        self.inner
+    }
+
+    /// Total number of constraints counted so far by the shared counter.
+    pub fn counted_constraints(&self) -> usize {
+        self.counter.get()
+    }
+
+    /// Applies the binary gadget `op` to the wrapped values and counts the
+    /// constraints it adds.
+    pub fn apply(
+        &self,
+        other: &Self,
+        op: impl FnOnce(&Int8<F>, &Int8<F>) -> Result<Int8<F>>,
+    ) -> Result<Self> {
+        let cs = self.inner.cs().or(other.inner.cs());
+        let constraints_before = cs.num_constraints();
+        let inner = op(&self.inner, &other.inner)?;
+        let added_constraints = cs
+            .num_constraints()
+            .checked_sub(constraints_before)
+            .ok_or_else(|| anyhow!("The number of constraints decreased"))?;
+        self.counter.set(self.counter.get() + added_constraints);
+        Ok(Self {
+            inner,
+            counter: Rc::clone(&self.counter),
+        })
+    }
+
+    pub fn add(&self, other: &Self) -> Result<Self> {
+        self.apply(other, |left, right| left.add(right))
+    }
+
+    pub fn sub(&self, other: &Self) -> Result<Self> {
+        self.apply(other, |left, right| left.sub(right))
+    }
+
+    pub fn and(&self, other: &Self) -> Result<Self> {
+        self.apply(other, |left, right| left.and(right))
+    }
+
+    pub fn or(&self, other: &Self) -> Result<Self> {
+        self.apply(other, |left, right| left.or(right))
+    }
+
+    pub fn xor(&self, other: &Self) -> Result<Self> {
+        self.apply(other, |left, right| left.xor(right))
+    }
+}
+
+#[cfg(test)]
+mod debug_tests {
+    use super::{constraint_fingerprint, CountingInt8};
+    use crate::gadgets::{traits::ArithmeticGadget, ConstraintF, Int8Gadget};
+    use ark_r1cs_std::{prelude::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use std::{cell::Cell, rc::Rc};
+
+    fn addition_circuit_fingerprint(augend: i8, addend: i8) -> u64 {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let augend_var = Int8Gadget::new_witness(cs.clone(), || Ok(augend)).unwrap();
+        let addend_var = Int8Gadget::new_witness(cs.clone(), || Ok(addend)).unwrap();
+        augend_var.add(&addend_var).unwrap();
+        constraint_fingerprint(&cs).unwrap()
+    }
+
+    #[test]
+    fn test_constraint_fingerprint_is_deterministic() {
+        assert_eq!(
+            addition_circuit_fingerprint(1, 2),
+            addition_circuit_fingerprint(1, 2)
+        );
+    }
+
+    #[test]
+    fn test_constraint_fingerprint_does_not_depend_on_the_assignment() {
+        assert_eq!(
+            addition_circuit_fingerprint(1, 2),
+            addition_circuit_fingerprint(-100, 42)
+        );
+    }
+
+    #[test]
+    fn test_constraint_fingerprint_changes_with_the_circuit_shape() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let minuend = Int8Gadget::new_witness(cs.clone(), || Ok(2)).unwrap();
+        let subtrahend = Int8Gadget::new_witness(cs.clone(), || Ok(1)).unwrap();
+        minuend.sub(&subtrahend).unwrap();
+
+        assert_ne!(
+            addition_circuit_fingerprint(2, 1),
+            constraint_fingerprint(&cs).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_constraint_fingerprint_leaves_the_constraint_system_untouched() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let augend = Int8Gadget::new_witness(cs.clone(), || Ok(1)).unwrap();
+        let addend = Int8Gadget::new_witness(cs.clone(), || Ok(2)).unwrap();
+        augend.add(&addend).unwrap();
+        let constraint_system_before = format!("{:?}", cs.borrow().unwrap());
+
+        constraint_fingerprint(&cs).unwrap();
+
+        assert_eq!(
+            constraint_system_before,
+            format!("{:?}", cs.borrow().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_counting_int8_counts_the_constraints_of_an_addition() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let augend = Int8Gadget::new_witness(cs.clone(), || Ok(3)).unwrap();
+        let addend = Int8Gadget::new_witness(cs.clone(), || Ok(-5)).unwrap();
+        let addition_constraints = {
+            let constraints_before = cs.num_constraints();
+            augend.add(&addend).unwrap();
+            cs.num_constraints() - constraints_before
+        };
+        let counter = Rc::new(Cell::new(0));
+        let counting_augend = CountingInt8::new(augend, Rc::clone(&counter));
+        let counting_addend = CountingInt8::new(addend, Rc::clone(&counter));
+
+        let sum = counting_augend.add(&counting_addend).unwrap();
+        let twice_sum = sum.add(&sum).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(-4, twice_sum.inner().value().unwrap());
+        assert!(addition_constraints > 0);
+        assert_eq!(2 * addition_constraints, counter.get());
+        assert_eq!(counter.get(), counting_augend.counted_constraints());
+    }
+
+    #[test]
+    fn test_counting_int8_of_constants_counts_nothing() {
+        let counter = Rc::new(Cell::new(0));
+        let left = CountingInt8::new(Int8Gadget::constant(6), Rc::clone(&counter));
+        let right = CountingInt8::new(Int8Gadget::constant(3), Rc::clone(&counter));
+
+        let result = left.xor(&right).unwrap().and(&right).unwrap();
+
+        assert_eq!(1, result.into_inner().value().unwrap());
+        assert_eq!(0, counter.get());
+    }
+}
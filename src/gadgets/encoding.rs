@@ -0,0 +1,388 @@
+use super::{helpers, int8::Int8};
+use anyhow::{anyhow, Result};
+use ark_ff::Field;
+use ark_r1cs_std::{
+    prelude::{Boolean, EqGadget},
+    select::CondSelectGadget,
+};
+use ark_relations::r1cs::SynthesisError;
+
+/// Converts an ASCII character into the signed byte holding it.
+fn ascii(character: u8) -> i8 {
+    i8::from_ne_bytes([character])
+}
+
+/// Adds the constant `offset` to `byte`, wrapping on overflow.
+fn wrapping_add_constant<F: Field>(byte: &Int8<F>, offset: i8) -> Result<Int8<F>> {
+    let [offset_byte] = offset.to_ne_bytes();
+    let offset_bits = helpers::constant_bits_le(u64::from(offset_byte), 8);
+    let (sum, _) = helpers::ripple_carry_add(byte.bits(), &offset_bits, Boolean::FALSE)?;
+    Int8::from_bits_le(&sum)
+}
+
+impl<F: Field> Int8<F> {
+    /// Flags for the upper case letters, lower case letters, digits and
+    /// symbols of the base64 alphabet.
+    fn base64_classes(&self) -> Result<[Boolean<F>; 4]> {
+        Ok([
+            self.is_in_range(ascii(b'A'), ascii(b'Z'))?,
+            self.is_in_range(ascii(b'a'), ascii(b'z'))?,
+            self.is_in_range(ascii(b'0'), ascii(b'9'))?,
+            self.is_in_set(&[ascii(b'+'), ascii(b'/')])?,
+        ])
+    }
+
+    /// Returns whether the byte is a character of the standard base64
+    /// alphabet (`A-Z`, `a-z`, `0-9`, `+` and `/`), excluding the `=` padding.
+    pub fn is_base64_char(&self) -> Result<Boolean<F>> {
+        Ok(Boolean::kary_or(&self.base64_classes()?)?)
+    }
+
+    /// Decodes a base64 character into its little-endian 6-bit value and
+    /// whether the character is valid. Invalid characters decode to zero.
+    pub fn base64_decode_char(&self) -> Result<([Boolean<F>; 6], Boolean<F>)> {
+        let [is_upper, is_lower, is_digit, is_symbol] = self.base64_classes()?;
+        // '+' (0x2b) and '/' (0x2f) only differ in bit 2, which tells 62 and
+        // 63 apart.
+        let slash_bit = self
+            .bits()
+            .get(2)
+            .cloned()
+            .ok_or_else(|| anyhow!("Error getting the bits of the character"))?;
+        let mut symbol_bits = vec![slash_bit];
+        symbol_bits.extend([Boolean::TRUE; 5]);
+        let candidates = [
+            (is_upper, wrapping_add_constant(self, -65)?),
+            (is_lower, wrapping_add_constant(self, -71)?),
+            (is_digit, wrapping_add_constant(self, 4)?),
+            (is_symbol, Self::pack_bits(&symbol_bits)?),
+        ];
+
+        let mut decoded = Self::constant(0);
+        let mut is_valid = Boolean::FALSE;
+        for (is_class, value) in candidates {
+            decoded = Self::conditionally_select(&is_class, &value, &decoded)?;
+            is_valid = is_valid.or(&is_class)?;
+        }
+
+        let [b0, b1, b2, b3, b4, b5, _, _] = decoded.bits().clone();
+        Ok(([b0, b1, b2, b3, b4, b5], is_valid))
+    }
+
+    /// Classifies the byte by its high bits as UTF-8 `(ascii, lead,
+    /// continuation)`: ASCII bytes are `0xxxxxxx`, continuation bytes are
+    /// `10xxxxxx` and lead bytes are `110xxxxx`, `1110xxxx` or `11110xxx`.
+    /// Bytes `11111xxx` belong to no class. Overlong leads (`0xc0`, `0xc1`)
+    /// and leads above `0xf4` are not told apart from the valid ones.
+    pub fn utf8_byte_class(&self) -> Result<(Boolean<F>, Boolean<F>, Boolean<F>)> {
+        let [_, _, _, b3, b4, b5, b6, b7] = self.bits().clone();
+        let is_ascii = b7.not();
+        let is_continuation = b7.and(&b6.not())?;
+        let is_lead = b7.and(&b6)?.and(&Boolean::kary_and(&[b5, b4, b3])?.not())?;
+        Ok((is_ascii, is_lead, is_continuation))
+    }
+
+    /// Returns whether the byte is a character of the Base58 alphabet, i.e.
+    /// an ASCII digit or letter other than `0`, `O`, `I` and `l`.
+    pub fn is_base58_char(&self) -> Result<Boolean<F>> {
+        let ranges = [
+            (b'1', b'9'),
+            (b'A', b'H'),
+            (b'J', b'N'),
+            (b'P', b'Z'),
+            (b'a', b'k'),
+            (b'm', b'z'),
+        ];
+        let in_range = ranges
+            .iter()
+            .map(|(low, high)| self.is_in_range(ascii(*low), ascii(*high)))
+            .collect::<Result<Vec<Boolean<F>>>>()?;
+        Ok(Boolean::kary_or(&in_range)?)
+    }
+
+    /// Returns whether the byte is a printable ASCII character, from the
+    /// space (`0x20`) to the tilde (`0x7e`).
+    pub fn is_printable_ascii(&self) -> Result<Boolean<F>> {
+        self.is_in_range(ascii(b' '), ascii(b'~'))
+    }
+
+    /// Converts `A-Z` to `a-z`, leaving any other byte unchanged. Upper case
+    /// letters have bit 5 cleared, so adding `0x20` to them just sets it.
+    pub fn ascii_to_lower(&self) -> Result<Self> {
+        let is_upper = self.is_in_range(ascii(b'A'), ascii(b'Z'))?;
+        self.with_case_bit(|case_bit| case_bit.or(&is_upper))
+    }
+
+    /// Converts `a-z` to `A-Z`, leaving any other byte unchanged. Lower case
+    /// letters have bit 5 set, so subtracting `0x20` from them just clears it.
+    pub fn ascii_to_upper(&self) -> Result<Self> {
+        let is_lower = self.is_in_range(ascii(b'a'), ascii(b'z'))?;
+        self.with_case_bit(|case_bit| case_bit.and(&is_lower.not()))
+    }
+
+    /// Replaces bit 5, which tells ASCII letter cases apart.
+    fn with_case_bit(
+        &self,
+        update: impl FnOnce(&Boolean<F>) -> Result<Boolean<F>, SynthesisError>,
+    ) -> Result<Self> {
+        let mut bits = self.bits().clone();
+        let case_bit = bits
+            .get_mut(5)
+            .ok_or_else(|| anyhow!("Error getting the case bit"))?;
+        *case_bit = update(case_bit)?;
+        Self::from_bits_le(&bits)
+    }
+}
+
+/// Enforces that `bytes` is well structured UTF-8: every lead byte is
+/// followed by exactly the number of continuation bytes its high bits
+/// announce, there are no stray continuation bytes and no sequence is cut
+/// short at the end. Overlong encodings, surrogates and code points above
+/// `U+10FFFF` are not rejected.
+pub fn enforce_valid_utf8<F: Field>(bytes: &[Int8<F>]) -> Result<()> {
+    // One-hot number of continuation bytes still expected, from 0 to 3.
+    let mut pending = [
+        Boolean::TRUE,
+        Boolean::FALSE,
+        Boolean::FALSE,
+        Boolean::FALSE,
+    ];
+    for byte in bytes {
+        let (is_ascii, is_lead, is_continuation) = byte.utf8_byte_class()?;
+        let [_, _, _, b3, b4, b5, _, _] = byte.bits().clone();
+        let [expects_none, expects_one, expects_two, expects_three] = pending;
+
+        let is_valid = expects_none
+            .and(&is_ascii.or(&is_lead)?)?
+            .or(&expects_none.not().and(&is_continuation)?)?;
+        is_valid.enforce_equal(&Boolean::TRUE)?;
+
+        let starts_sequence = expects_none.and(&is_lead)?;
+        let starts_two_bytes = starts_sequence.and(&b5.not())?;
+        let starts_three_bytes = starts_sequence.and(&b5)?.and(&b4.not())?;
+        let starts_four_bytes = starts_sequence.and(&b5)?.and(&b4)?.and(&b3.not())?;
+        pending = [
+            expects_one.or(&expects_none.and(&is_ascii)?)?,
+            expects_two.or(&starts_two_bytes)?,
+            expects_three.or(&starts_three_bytes)?,
+            starts_four_bytes,
+        ];
+    }
+    let [expects_none, _, _, _] = pending;
+    expects_none.enforce_equal(&Boolean::TRUE)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod encoding_tests {
+    use super::enforce_valid_utf8;
+    use crate::gadgets::{ConstraintF, Int8Gadget};
+    use ark_r1cs_std::{prelude::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_is_base64_char() {
+        let valid = b"AZaz09+/Mq5";
+        let invalid = b"=-_ @[`{:\n\x7f";
+        for (characters, expected) in [(valid.as_slice(), true), (invalid.as_slice(), false)] {
+            for character in characters {
+                let cs = ConstraintSystem::<ConstraintF>::new_ref();
+                let byte =
+                    Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([*character])))
+                        .unwrap();
+
+                let is_base64 = byte.is_base64_char().unwrap();
+
+                assert!(cs.is_satisfied().unwrap());
+                assert_eq!(expected, is_base64.value().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_base64_char_rejects_non_ascii_bytes() {
+        for value in [-128_i8, -1, -63] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            assert!(!byte.is_base64_char().unwrap().value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_base64_decode_char() {
+        let alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        for (expected, character) in (0_u8..).zip(alphabet) {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([*character])))
+                .unwrap();
+
+            let (bits, is_valid) = byte.base64_decode_char().unwrap();
+
+            let value = bits.iter().rev().fold(0_u8, |value, bit| {
+                (value << 1_u8) | u8::from(bit.value().unwrap())
+            });
+            assert!(cs.is_satisfied().unwrap());
+            assert!(is_valid.value().unwrap());
+            assert_eq!(expected, value);
+        }
+    }
+
+    #[test]
+    fn test_base64_decode_char_with_invalid_characters() {
+        for character in b"=-_ @[`{:\n\x7f\x80\xff" {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([*character])))
+                .unwrap();
+
+            let (bits, is_valid) = byte.base64_decode_char().unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert!(!is_valid.value().unwrap());
+            assert!(bits.iter().all(|bit| !bit.value().unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_utf8_byte_class() {
+        // (byte, ascii, lead, continuation)
+        let cases = [
+            (0x00_u8, true, false, false),
+            (b'a', true, false, false),
+            (0x7f, true, false, false),
+            (0x80, false, false, true),
+            (0xa9, false, false, true),
+            (0xbf, false, false, true),
+            (0xc3, false, true, false),
+            (0xe2, false, true, false),
+            (0xf0, false, true, false),
+            (0xf7, false, true, false),
+            (0xf8, false, false, false),
+            (0xff, false, false, false),
+        ];
+        for (byte, ascii, lead, continuation) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte =
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([byte]))).unwrap();
+
+            let (is_ascii, is_lead, is_continuation) = byte.utf8_byte_class().unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(ascii, is_ascii.value().unwrap());
+            assert_eq!(lead, is_lead.value().unwrap());
+            assert_eq!(continuation, is_continuation.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_valid_utf8_with_valid_sequences() {
+        for text in [
+            "",
+            "hello",
+            "ñandú",
+            "€ 10",
+            "日本語",
+            "🦀 rust 🦀",
+            "a\u{10ffff}",
+        ] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec_from_u8(cs.clone(), text.as_bytes()).unwrap();
+
+            enforce_valid_utf8(&bytes).unwrap();
+
+            assert!(cs.is_satisfied().unwrap(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_enforce_valid_utf8_with_malformed_sequences_is_not_satisfied() {
+        let malformed: [&[u8]; 7] = [
+            b"\x80",
+            b"a\xbfb",
+            b"\xc3",
+            b"\xc3a",
+            b"\xe2\x82",
+            b"\xf0\x9f\xa6\x80\x80",
+            b"\xff",
+        ];
+        for bytes in malformed {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let bytes = Int8Gadget::new_witness_vec_from_u8(cs.clone(), bytes).unwrap();
+
+            enforce_valid_utf8(&bytes).unwrap();
+
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_ascii_case_folding() {
+        // (character, lower case, upper case)
+        let cases = [
+            (b'A', b'a', b'A'),
+            (b'a', b'a', b'A'),
+            (b'z', b'z', b'Z'),
+            (b'Z', b'z', b'Z'),
+            (b'5', b'5', b'5'),
+            (b'@', b'@', b'@'),
+            (b'[', b'[', b'['),
+            (b'`', b'`', b'`'),
+            (b'{', b'{', b'{'),
+            (0xc1, 0xc1, 0xc1),
+        ];
+        for (character, lower, upper) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte =
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([character]))).unwrap();
+
+            let lower_case = byte.ascii_to_lower().unwrap();
+            let upper_case = byte.ascii_to_upper().unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(i8::from_ne_bytes([lower]), lower_case.value().unwrap());
+            assert_eq!(i8::from_ne_bytes([upper]), upper_case.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_is_printable_ascii() {
+        let printable = b" ~azAZ09!/";
+        let non_printable = b"\x7f\x1f\x00\n\t\x80\xff";
+        for (characters, expected) in [
+            (printable.as_slice(), true),
+            (non_printable.as_slice(), false),
+        ] {
+            for character in characters {
+                let cs = ConstraintSystem::<ConstraintF>::new_ref();
+                let byte =
+                    Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([*character])))
+                        .unwrap();
+
+                let is_printable = byte.is_printable_ascii().unwrap();
+
+                assert!(cs.is_satisfied().unwrap());
+                assert_eq!(expected, is_printable.value().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_base58_char() {
+        let valid = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+        let invalid = b"0OIl+/=_ \n\x7f\x80\xff";
+        for (characters, expected) in [(valid.as_slice(), true), (invalid.as_slice(), false)] {
+            for character in characters {
+                let cs = ConstraintSystem::<ConstraintF>::new_ref();
+                let byte =
+                    Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([*character])))
+                        .unwrap();
+
+                let is_base58 = byte.is_base58_char().unwrap();
+
+                assert!(cs.is_satisfied().unwrap());
+                assert_eq!(expected, is_base58.value().unwrap());
+            }
+        }
+    }
+}
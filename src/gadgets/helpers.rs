@@ -1,12 +1,15 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 use ark_ff::Field;
 use ark_r1cs_std::ToBitsGadget;
 use ark_r1cs_std::{
-    prelude::{AllocVar, Boolean},
+    prelude::{AllocVar, Boolean, EqGadget},
     select::CondSelectGadget,
     R1CSVar,
 };
-use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSystemRef, LinearCombination, SynthesisError, Variable},
+};
 
 use super::{int8::Int8, traits::ArithmeticGadget};
 
@@ -48,6 +51,229 @@ where
     Ok(result)
 }
 
+/// Ripple-carry addition of two little-endian bit vectors of the same length.
+/// Returns the sum bits together with the carry out of the most significant bit.
+pub(crate) fn ripple_carry_add<F: Field>(
+    augend: &[Boolean<F>],
+    addend: &[Boolean<F>],
+    carry_in: Boolean<F>,
+) -> Result<(Vec<Boolean<F>>, Boolean<F>)> {
+    ensure!(
+        augend.len() == addend.len(),
+        "Operands must have the same number of bits"
+    );
+    let mut sum = Vec::with_capacity(augend.len());
+    let mut carry = carry_in;
+    for (augend_bit, addend_bit) in augend.iter().zip(addend) {
+        // sum[i] = augend_bit ^ addend_bit ^ carry
+        // carry  = (augend_bit & addend_bit) | (carry & (augend_bit ^ addend_bit))
+        let partial_sum = augend_bit.xor(addend_bit)?;
+        sum.push(partial_sum.xor(&carry)?);
+        carry = augend_bit.and(addend_bit)?.or(&carry.and(&partial_sum)?)?;
+    }
+    Ok((sum, carry))
+}
+
+/// Extends a little-endian two's complement bit vector to `width` bits by
+/// repeating its most significant bit.
+pub(crate) fn sign_extend<F: Field>(bits: &[Boolean<F>], width: usize) -> Result<Vec<Boolean<F>>> {
+    let sign = bits
+        .last()
+        .ok_or_else(|| anyhow!("Cannot sign extend an empty bit vector"))?;
+    ensure!(
+        width >= bits.len(),
+        "Cannot sign extend to a narrower width"
+    );
+    let mut extended = bits.to_vec();
+    extended.resize(width, sign.clone());
+    Ok(extended)
+}
+
+/// Returns whether the two's complement integer represented by `left_bits` is
+/// strictly less than the one represented by `right_bits`. Both operands are
+/// sign extended by one bit before subtracting so that the sign of the
+/// difference is always correct.
+pub(crate) fn is_less_than_signed<F: Field>(
+    left_bits: &[Boolean<F>],
+    right_bits: &[Boolean<F>],
+) -> Result<Boolean<F>> {
+    ensure!(
+        left_bits.len() == right_bits.len(),
+        "Operands must have the same number of bits"
+    );
+    let width = left_bits.len() + 1;
+    let minuend = sign_extend(left_bits, width)?;
+    let negated_subtrahend = sign_extend(right_bits, width)?
+        .iter()
+        .map(Boolean::not)
+        .collect::<Vec<Boolean<F>>>();
+    let (difference, _) = ripple_carry_add(&minuend, &negated_subtrahend, Boolean::TRUE)?;
+    difference
+        .last()
+        .cloned()
+        .ok_or_else(|| anyhow!("Error getting the sign of the difference"))
+}
+
+/// Returns whether the unsigned integer represented by `left_bits` is
+/// strictly less than the one represented by `right_bits`.
+pub(crate) fn is_less_than_unsigned<F: Field>(
+    left_bits: &[Boolean<F>],
+    right_bits: &[Boolean<F>],
+) -> Result<Boolean<F>> {
+    let zero_extend = |bits: &[Boolean<F>]| {
+        let mut extended = bits.to_vec();
+        extended.push(Boolean::FALSE);
+        extended
+    };
+    is_less_than_signed(&zero_extend(left_bits), &zero_extend(right_bits))
+}
+
+/// Linear combination evaluating to the unsigned value of little-endian bits.
+pub(crate) fn unsigned_lc<F: Field>(bits: &[Boolean<F>]) -> LinearCombination<F> {
+    let mut value_lc = lc!();
+    let mut coefficient = F::one();
+    for bit in bits {
+        value_lc = value_lc + bit.lc() * coefficient;
+        coefficient.double_in_place();
+    }
+    value_lc
+}
+
+/// Linear combination evaluating to the two's complement value of
+/// little-endian bits.
+pub(crate) fn signed_lc<F: Field>(bits: &[Boolean<F>]) -> LinearCombination<F> {
+    match bits.split_last() {
+        Some((sign, magnitude)) => {
+            let mut sign_weight = F::one();
+            for _ in magnitude {
+                sign_weight.double_in_place();
+            }
+            unsigned_lc(magnitude) - sign.lc() * sign_weight
+        }
+        None => lc!(),
+    }
+}
+
+/// Counts the set `bits`, returning the little-endian bits of the count,
+/// just wide enough to hold `bits.len()`. The prover supplies the count and
+/// a single linear constraint binds it to the sum of the bits.
+pub(crate) fn count_ones<F: Field>(bits: &[Boolean<F>]) -> Result<Vec<Boolean<F>>> {
+    let width = usize::try_from(usize::BITS - bits.len().leading_zeros())?;
+    let count_value = || -> Result<u64, SynthesisError> {
+        bits.iter()
+            .try_fold(0_u64, |count, bit| Ok(count + u64::from(bit.value()?)))
+    };
+
+    let cs = bits.cs();
+    if cs.is_none() {
+        return Ok(constant_bits_le(count_value()?, width));
+    }
+
+    let count = (0..width)
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok((count_value()? >> i) & 1_u64 == 1_u64)))
+        .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+    let sum_lc = bits.iter().fold(lc!(), |sum_lc, bit| sum_lc + bit.lc());
+    cs.enforce_constraint(lc!() + Variable::One, sum_lc, unsigned_lc(&count))?;
+    Ok(count)
+}
+
+/// Multiplies the unsigned integers represented by the little-endian `left`
+/// and `right` bits, returning the bits of the full product. The prover
+/// supplies them and a single constraint binds them to the factors.
+pub(crate) fn unsigned_mul<F: Field>(
+    left: &[Boolean<F>],
+    right: &[Boolean<F>],
+) -> Result<Vec<Boolean<F>>> {
+    let width = left.len() + right.len();
+    ensure!(width <= 64, "The product can't be wider than 64 bits");
+    let product_value =
+        || -> Result<u64, SynthesisError> { Ok(unsigned_value(left)? * unsigned_value(right)?) };
+
+    let cs = left.cs().or(right.cs());
+    if cs.is_none() {
+        return Ok(constant_bits_le(product_value()?, width));
+    }
+
+    let product = (0..width)
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok((product_value()? >> i) & 1_u64 == 1_u64)))
+        .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+    cs.enforce_constraint(unsigned_lc(left), unsigned_lc(right), unsigned_lc(&product))?;
+    Ok(product)
+}
+
+/// Unsigned value of (at most 64) little-endian bits.
+pub(crate) fn unsigned_value<F: Field>(bits: &[Boolean<F>]) -> Result<u64, SynthesisError> {
+    bits.iter().enumerate().try_fold(0_u64, |value, (i, bit)| {
+        Ok(value | (u64::from(bit.value()?) << i))
+    })
+}
+
+/// Little-endian constant bits of the `width` least significant bits of `value`.
+pub(crate) fn constant_bits_le<F: Field>(value: u64, width: usize) -> Vec<Boolean<F>> {
+    (0..width)
+        .map(|i| Boolean::constant((value >> i) & 1_u64 == 1_u64))
+        .collect()
+}
+
+/// Little-endian bits of a quotient and a remainder.
+pub(crate) type QuotientAndRemainder<F> = (Vec<Boolean<F>>, Vec<Boolean<F>>);
+
+/// Divides the unsigned integer represented by the little-endian `dividend`
+/// bits (at most 32) by a constant. Returns the bits of the quotient, as wide
+/// as the dividend, and the 8 bits of the remainder.
+///
+/// The prover supplies both and we enforce
+/// `dividend = divisor * quotient + remainder` and `remainder < divisor`.
+/// Every term is below 2^40, so the relation can't wrap around the field.
+pub(crate) fn unsigned_div_rem_constant<F: Field>(
+    dividend: &[Boolean<F>],
+    divisor: u8,
+) -> Result<QuotientAndRemainder<F>> {
+    ensure!(divisor != 0, "attempt to divide by zero");
+    ensure!(
+        dividend.len() <= 32,
+        "The dividend can't be wider than 32 bits"
+    );
+    let quotient_value =
+        || -> Result<u64, SynthesisError> { Ok(unsigned_value(dividend)? / u64::from(divisor)) };
+    let remainder_value =
+        || -> Result<u64, SynthesisError> { Ok(unsigned_value(dividend)? % u64::from(divisor)) };
+
+    let cs = dividend.cs();
+    if cs.is_none() {
+        return Ok((
+            constant_bits_le(quotient_value()?, dividend.len()),
+            constant_bits_le(remainder_value()?, 8),
+        ));
+    }
+
+    let quotient = (0..dividend.len())
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok((quotient_value()? >> i) & 1_u64 == 1_u64)))
+        .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+    let remainder = (0_usize..8_usize)
+        .map(|i| {
+            Boolean::new_witness(
+                cs.clone(),
+                || Ok((remainder_value()? >> i) & 1_u64 == 1_u64),
+            )
+        })
+        .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+
+    cs.enforce_constraint(
+        unsigned_lc(&quotient),
+        lc!() + (F::from(divisor), Variable::One),
+        unsigned_lc(dividend) - unsigned_lc(&remainder),
+    )?;
+
+    // Zero extend both sides so the signed comparison acts as an unsigned one.
+    let mut extended_remainder = remainder.clone();
+    extended_remainder.push(Boolean::FALSE);
+    let extended_divisor = constant_bits_le(u64::from(divisor), 9);
+    is_less_than_signed(&extended_remainder, &extended_divisor)?.enforce_equal(&Boolean::TRUE)?;
+
+    Ok((quotient, remainder))
+}
+
 pub(crate) fn compare_ord<F: Field, T: R1CSVar<F>>(
     left_operand: T,
     right_operand: T,
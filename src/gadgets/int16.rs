@@ -0,0 +1,55 @@
+use super::int_macros::{impl_from_bytes, impl_signed_int};
+use ark_ff::Field;
+use ark_r1cs_std::prelude::Boolean;
+
+#[derive(Clone, Debug)]
+pub struct Int16<F: Field> {
+    /// Little-endian representation: least significant bit first
+    pub(crate) bits: [Boolean<F>; 16],
+    /// Only read back by the `strict-value-checks` assertion in `value()`.
+    #[cfg_attr(not(any(test, feature = "strict-value-checks")), allow(dead_code))]
+    pub(crate) value: Option<i16>,
+}
+
+impl_signed_int!(Int16, i16, 16);
+impl_from_bytes!(Int16, 2);
+
+#[cfg(test)]
+mod int16_tests {
+    use crate::gadgets::{ConstraintF, Int16Gadget, Int8Gadget};
+    use ark_r1cs_std::{prelude::AllocVar, R1CSVar, ToBytesGadget};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_new_witness_and_value() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        for value in [0_i16, 1_i16, -1_i16, i16::MIN, i16::MAX, 12_345_i16] {
+            let int16 = Int16Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            assert_eq!(value, int16.value().unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_from_le_and_be_bytes() {
+        for value in [0x1234_i16, -2_i16, i16::MIN, i16::MAX] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let le_bytes = value.to_le_bytes().map(|byte| i8::from_ne_bytes([byte]));
+            let be_bytes = value.to_be_bytes().map(|byte| i8::from_ne_bytes([byte]));
+            let le_bytes = Int8Gadget::new_witness_vec(cs.clone(), &le_bytes).unwrap();
+            let be_bytes = Int8Gadget::new_witness_vec(cs.clone(), &be_bytes).unwrap();
+
+            let from_le = Int16Gadget::from_le_bytes(&le_bytes.try_into().unwrap()).unwrap();
+            let from_be = Int16Gadget::from_be_bytes(&be_bytes.try_into().unwrap()).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(value, from_le.value().unwrap());
+            assert_eq!(value, from_be.value().unwrap());
+            assert_eq!(
+                value.to_le_bytes().to_vec(),
+                from_le.to_bytes().unwrap().value().unwrap()
+            );
+        }
+    }
+}
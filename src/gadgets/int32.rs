@@ -0,0 +1,311 @@
+use super::{
+    helpers,
+    int8::Int8,
+    int_macros::{impl_from_bytes, impl_signed_int},
+    traits::IntGadget,
+};
+use anyhow::{anyhow, ensure, Result};
+use ark_ff::Field;
+use ark_r1cs_std::{
+    prelude::{AllocVar, Boolean, EqGadget},
+    select::CondSelectGadget,
+    R1CSVar,
+};
+use ark_relations::{lc, r1cs::Variable};
+
+#[derive(Clone, Debug)]
+pub struct Int32<F: Field> {
+    /// Little-endian representation: least significant bit first
+    pub(crate) bits: [Boolean<F>; 32],
+    /// Only read back by the `strict-value-checks` assertion in `value()`.
+    #[cfg_attr(not(any(test, feature = "strict-value-checks")), allow(dead_code))]
+    pub(crate) value: Option<i32>,
+}
+
+impl_signed_int!(Int32, i32, 32);
+impl_from_bytes!(Int32, 4);
+
+impl<F: Field> Int32<F> {
+    /// Splits the integer into its two's complement bytes, least significant
+    /// first, the inverse of `from_le_bytes`. No constraints are added.
+    pub fn to_twos_complement_bytes(&self) -> Result<[Int8<F>; 4]> {
+        let bytes = self
+            .bits
+            .chunks(8)
+            .map(Int8::from_bits_le)
+            .collect::<Result<Vec<Int8<F>>>>()?;
+        bytes
+            .try_into()
+            .map_err(|_e| anyhow!("Error splitting an Int32 into 4 bytes"))
+    }
+}
+
+/// Sign extends `byte` and adds it to a 32-bit accumulator. On overflow the
+/// result saturates to `i32::MAX` or `i32::MIN` instead of wrapping.
+pub fn accumulate_i32<F: Field>(acc: &Int32<F>, byte: &Int8<F>) -> Result<Int32<F>> {
+    let addend = helpers::sign_extend(&byte.bits, 32)?;
+    let (sum, _) = helpers::ripple_carry_add(&acc.bits, &addend, Boolean::FALSE)?;
+
+    let acc_sign = acc
+        .bits
+        .last()
+        .ok_or_else(|| anyhow!("Error getting the sign of the accumulator"))?;
+    let addend_sign = addend
+        .last()
+        .ok_or_else(|| anyhow!("Error getting the sign of the addend"))?;
+    let sum_sign = sum
+        .last()
+        .ok_or_else(|| anyhow!("Error getting the sign of the sum"))?;
+    // The addition overflows iff both operands have the same sign and the sign
+    // of the sum differs from it.
+    let overflow = acc_sign
+        .xor(addend_sign)?
+        .not()
+        .and(&sum_sign.xor(acc_sign)?)?;
+
+    // i32::MIN is 1 followed by 31 zeros and i32::MAX is 0 followed by 31 ones,
+    // so the saturated value is the accumulator sign followed by its negation.
+    let mut saturated_bits = vec![acc_sign.not(); 31];
+    saturated_bits.push(acc_sign.clone());
+    let saturated = Int32::from_bits_le(&saturated_bits)?;
+
+    Ok(Int32::conditionally_select(
+        &overflow,
+        &saturated,
+        &Int32::from_bits_le(&sum)?,
+    )?)
+}
+
+/// Computes `sum(values[i] * weights[i])` as an Int32. The weights are
+/// constants, so the sum is linear in the bits of the values and only a
+/// single constraint is needed to bind it to the bits of the result.
+pub fn weighted_sum<F: Field>(values: &[Int8<F>], weights: &[i8]) -> Result<Int32<F>> {
+    ensure!(
+        values.len() == weights.len(),
+        "Expected {} weights but got {}",
+        values.len(),
+        weights.len()
+    );
+    // Every term is within (-2^14, 2^14], so the sum cannot overflow.
+    ensure!(
+        values.len() <= 1_usize << 16_u32,
+        "Too many terms, the weighted sum could overflow an Int32"
+    );
+
+    let sum_value = || {
+        values
+            .iter()
+            .zip(weights)
+            .try_fold(0_i32, |sum, (value, weight)| {
+                Ok(sum + i32::from(value.value()?) * i32::from(*weight))
+            })
+    };
+    let cs = values.cs();
+    if cs.is_none() {
+        return Ok(Int32::constant(sum_value()?));
+    }
+
+    let mut sum_lc = lc!();
+    for (value, weight) in values.iter().zip(weights) {
+        let magnitude = F::from(weight.unsigned_abs());
+        let coefficient = if weight.is_negative() {
+            -magnitude
+        } else {
+            magnitude
+        };
+        sum_lc = sum_lc + helpers::signed_lc(&value.bits) * coefficient;
+    }
+    let sum = Int32::new_witness(cs.clone(), sum_value)?;
+    cs.enforce_constraint(lc!() + Variable::One, sum_lc, helpers::signed_lc(&sum.bits))?;
+    Ok(sum)
+}
+
+/// Enforces that `timestamps` never decrease, comparing consecutive ones as
+/// signed integers.
+pub fn enforce_timestamps_monotonic<F: Field>(timestamps: &[Int32<F>]) -> Result<()> {
+    for (previous, next) in timestamps.iter().zip(timestamps.iter().skip(1)) {
+        next.is_lt(previous)?.enforce_equal(&Boolean::FALSE)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod int32_tests {
+    use super::{accumulate_i32, enforce_timestamps_monotonic, weighted_sum};
+    use crate::gadgets::{ConstraintF, Int32Gadget, Int8Gadget};
+    use ark_r1cs_std::{
+        prelude::{AllocVar, Boolean},
+        R1CSVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_new_witness_and_value() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        for value in [0_i32, 1_i32, -1_i32, i32::MIN, i32::MAX, 123_456_789_i32] {
+            let int32 = Int32Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            assert_eq!(value, int32.value().unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_accumulate_i32() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let primitive_bytes = (0_i32..300_i32)
+            .map(|i| i8::try_from(i % 256_i32 - 128_i32).unwrap())
+            .collect::<Vec<i8>>();
+        let expected_sum = primitive_bytes.iter().copied().map(i32::from).sum::<i32>();
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+
+        let mut acc = Int32Gadget::new_witness(cs.clone(), || Ok(0_i32)).unwrap();
+        for byte in &bytes {
+            acc = accumulate_i32(&acc, byte).unwrap();
+        }
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(expected_sum, acc.value().unwrap());
+    }
+
+    #[test]
+    fn test_accumulate_i32_saturates_on_overflow() {
+        let cases = [
+            (i32::MAX - 10_i32, 100_i8, i32::MAX),
+            (i32::MIN + 5_i32, -128, i32::MIN),
+            (i32::MAX, -1, i32::MAX - 1_i32),
+        ];
+        for (acc, byte, expected) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let acc = Int32Gadget::new_witness(cs.clone(), || Ok(acc)).unwrap();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(byte)).unwrap();
+
+            let result = accumulate_i32(&acc, &byte).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, result.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_from_le_bytes() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let primitive_bytes = [0x78_u8, 0x56, 0x34, 0x12].map(|byte| i8::from_ne_bytes([byte]));
+        let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+
+        let result = Int32Gadget::from_le_bytes(&bytes.try_into().unwrap()).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(0x1234_5678_i32, result.value().unwrap());
+    }
+
+    #[test]
+    fn test_from_be_bytes() {
+        for value in [0x1234_5678_i32, -2_i32, i32::MIN, i32::MAX] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let primitive_bytes = value.to_be_bytes().map(|byte| i8::from_ne_bytes([byte]));
+            let bytes = Int8Gadget::new_witness_vec(cs.clone(), &primitive_bytes).unwrap();
+            let num_constraints = cs.num_constraints();
+
+            let result = Int32Gadget::from_be_bytes(&bytes.try_into().unwrap()).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(num_constraints, cs.num_constraints());
+            assert_eq!(
+                i32::from_be_bytes(primitive_bytes.map(|byte| byte.to_ne_bytes()[0])),
+                result.value().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_be_bytes_of_constants() {
+        let bytes =
+            [0x7f_u8, 0xff, 0xff, 0xfe].map(|byte| Int8Gadget::constant(i8::from_ne_bytes([byte])));
+
+        let result = Int32Gadget::from_be_bytes(&bytes).unwrap();
+
+        assert!(result.bits.iter().all(Boolean::is_constant));
+        assert_eq!(0x7fff_fffe_i32, result.value().unwrap());
+    }
+
+    #[test]
+    fn test_weighted_sum() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let primitive_values = [127_i8, -128, 5, 0, -1, 100];
+        let weights = [127_i8, -128, -3, 42, 7, 0];
+        let values = Int8Gadget::new_witness_vec(cs.clone(), &primitive_values).unwrap();
+
+        let sum = weighted_sum(&values, &weights).unwrap();
+
+        let expected = primitive_values
+            .iter()
+            .zip(&weights)
+            .map(|(value, weight)| i32::from(*value) * i32::from(*weight))
+            .sum::<i32>();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(expected, sum.value().unwrap());
+    }
+
+    #[test]
+    fn test_weighted_sum_of_constants() {
+        let values = [Int8Gadget::constant(-128), Int8Gadget::constant(-128)];
+
+        let sum = weighted_sum(&values, &[-128, -128]).unwrap();
+
+        assert_eq!(2_i32 * 128_i32 * 128_i32, sum.value().unwrap());
+    }
+
+    #[test]
+    fn test_weighted_sum_with_length_mismatch_fails() {
+        let values = [Int8Gadget::constant(1), Int8Gadget::constant(2)];
+
+        assert!(weighted_sum(&values, &[1]).is_err());
+    }
+
+    #[test]
+    fn test_to_twos_complement_bytes() {
+        for value in [0x1234_5678_i32, -2_i32, i32::MIN, i32::MAX, 0_i32] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let integer = Int32Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let num_constraints = cs.num_constraints();
+
+            let bytes = integer.to_twos_complement_bytes().unwrap();
+
+            assert_eq!(num_constraints, cs.num_constraints());
+            assert_eq!(
+                value.to_le_bytes().map(|byte| i8::from_ne_bytes([byte])),
+                bytes.clone().map(|byte| byte.value().unwrap())
+            );
+            assert_eq!(
+                value,
+                Int32Gadget::from_le_bytes(&bytes).unwrap().value().unwrap()
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_timestamps_monotonic() {
+        // (timestamps, is monotonic)
+        let cases: [(&[i32], bool); 6] = [
+            (&[], true),
+            (&[1_700_000_000], true),
+            (&[-5, 0, 0, 1_700_000_000, i32::MAX], true),
+            (&[i32::MIN, -1, 1], true),
+            (&[1_700_000_000, 1_699_999_999], false),
+            (&[0, 10, 20, 15, 30], false),
+        ];
+        for (timestamps, is_monotonic) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let timestamps = timestamps
+                .iter()
+                .map(|timestamp| Int32Gadget::new_witness(cs.clone(), || Ok(*timestamp)).unwrap())
+                .collect::<Vec<_>>();
+
+            enforce_timestamps_monotonic(&timestamps).unwrap();
+
+            assert_eq!(is_monotonic, cs.is_satisfied().unwrap());
+        }
+    }
+}
@@ -1,26 +1,28 @@
-use std::borrow::Borrow;
-
 use crate::gadgets::Comparison;
 
 use super::{
+    constant_pool::ConstantPool,
     helpers::{self, zip_bits_and_apply},
+    int_macros::impl_signed_int,
     traits::{
         ArithmeticGadget, BitManipulationGadget, BitwiseOperationGadget, ComparisonGadget,
-        IsWitness,
+        IntGadget,
     },
 };
 use anyhow::{anyhow, ensure, Result};
-use ark_ff::Field;
+use ark_ff::{Field, PrimeField};
 use ark_r1cs_std::{
-    prelude::{AllocVar, AllocationMode, Boolean, EqGadget},
+    fields::fp::FpVar,
+    prelude::{AllocVar, Boolean, EqGadget},
     select::CondSelectGadget,
     uint8::UInt8,
-    Assignment, R1CSVar, ToBitsGadget, ToBytesGadget,
+    Assignment, R1CSVar, ToBitsGadget,
 };
 use ark_relations::{
     lc,
-    r1cs::{ConstraintSystemRef, Namespace, SynthesisError},
+    r1cs::{ConstraintSystemRef, LinearCombination, Namespace, SynthesisError, Variable},
 };
+use rand::Rng;
 
 #[derive(Clone, Debug)]
 pub struct Int8<F: Field> {
@@ -28,152 +30,680 @@ pub struct Int8<F: Field> {
     pub(crate) value: Option<i8>,
 }
 
-impl<F: Field> IsWitness<F> for Int8<F> {}
+/// Status flags of an addition, mirroring a CPU flags register.
+#[derive(Clone, Debug)]
+pub struct AddFlags<F: Field> {
+    /// Set iff the unsigned sum does not fit in a byte.
+    pub carry_out: Boolean<F>,
+    /// Set iff the signed sum does not fit in an `i8`.
+    pub overflow: Boolean<F>,
+    /// Set iff the (wrapped) result is zero.
+    pub zero: Boolean<F>,
+}
 
-impl<F: Field> ToBytesGadget<F> for Int8<F> {
-    fn to_bytes(&self) -> Result<Vec<ark_r1cs_std::uint8::UInt8<F>>, SynthesisError> {
-        Ok(self
-            .to_bits_le()?
-            .chunks(8)
-            .map(UInt8::from_bits_le)
-            .collect())
-    }
+/// Where the value of a byte allocated by `Int8::new_mixed_vec` comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteSource {
+    /// Known when building the circuit, so it costs nothing.
+    Constant(i8),
+    /// Secret, allocated as a witness.
+    Witness(i8),
 }
 
-impl<ConstraintF: Field> AllocVar<i8, ConstraintF> for Int8<ConstraintF> {
-    fn new_variable<T: Borrow<i8>>(
-        cs: impl Into<Namespace<ConstraintF>>,
-        f: impl FnOnce() -> Result<T, SynthesisError>,
-        mode: AllocationMode,
-    ) -> Result<Self, SynthesisError> {
+impl_signed_int!(Int8, i8, 8);
+
+impl<F: Field> Int8<F> {
+    /// Returns the little-endian bits of the byte without cloning them.
+    pub fn bits(&self) -> &[Boolean<F>; 8] {
+        &self.bits
+    }
+
+    /// Allocates a vector of `Int8` witnesses, one per value.
+    pub fn new_witness_vec(
+        cs: impl Into<Namespace<F>>,
+        values: &[impl Into<Option<i8>> + Copy],
+    ) -> Result<Vec<Self>, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let mut output_vec = Vec::with_capacity(values.len());
+        for value in values {
+            let byte: Option<i8> = Into::into(*value);
+            output_vec.push(Self::new_witness(cs.clone(), || byte.get())?);
+        }
+        Ok(output_vec)
+    }
+
+    /// Allocates one `Int8` per item, as a constant or a witness depending on
+    /// its source. Repeated constants share the same bits.
+    pub fn new_mixed_vec(
+        cs: impl Into<Namespace<F>>,
+        items: &[ByteSource],
+    ) -> Result<Vec<Self>, SynthesisError> {
         let ns = cs.into();
         let cs = ns.cs();
-        let value = f().map(|f| *f.borrow()).ok();
+        let mut constants = ConstantPool::new();
+        items
+            .iter()
+            .map(|item| match *item {
+                ByteSource::Constant(value) => Ok(constants.get(value)),
+                ByteSource::Witness(value) => Self::new_witness(cs.clone(), || Ok(value)),
+            })
+            .collect()
+    }
+
+    /// Allocates a witness with a uniformly random value drawn from `rng`,
+    /// which is handy for fuzzing circuits with a seeded generator.
+    pub fn random_witness(
+        cs: impl Into<Namespace<F>>,
+        rng: &mut impl Rng,
+    ) -> Result<Self, SynthesisError> {
+        let value: i8 = rng.gen();
+        Self::new_witness(cs, || Ok(value))
+    }
+
+    /// Allocates one `Int8` witness per byte of a hex encoded string, e.g.
+    /// `"deadbeef"`. Errors on odd length or non-hex characters.
+    pub fn new_witness_vec_from_hex(cs: impl Into<Namespace<F>>, hex: &str) -> Result<Vec<Self>> {
+        Ok(Self::new_witness_vec_from_u8(cs, &hex::decode(hex)?)?)
+    }
+
+    /// Allocates one `Int8` witness per unsigned byte, keeping its bits, so
+    /// e.g. `0xff` becomes `-1`.
+    pub fn new_witness_vec_from_u8(
+        cs: impl Into<Namespace<F>>,
+        bytes: &[u8],
+    ) -> Result<Vec<Self>, SynthesisError> {
+        let bytes = bytes
+            .iter()
+            .map(|byte| i8::from_ne_bytes([*byte]))
+            .collect::<Vec<i8>>();
+        Self::new_witness_vec(cs, &bytes)
+    }
+
+    /// Packs up to 8 little-endian bits into an `Int8`, padding the missing
+    /// most significant bits with zeros. Unlike `from_bits_le` this does not
+    /// require exactly 8 bits, but it errors if more than 8 are given.
+    pub fn pack_bits(bits: &[Boolean<F>]) -> Result<Self> {
+        ensure!(
+            bits.len() <= 8,
+            "Cannot pack {} bits into an Int8",
+            bits.len()
+        );
+        let mut padded_bits = bits.to_vec();
+        padded_bits.resize(8, Boolean::FALSE);
+        Self::from_bits_le(&padded_bits)
+    }
+
+    /// Builds a signed and an unsigned view over the same bits. No new
+    /// variables nor constraints are added, so circuits mixing signed and
+    /// unsigned operations on a byte don't need to allocate it twice.
+    pub fn reinterpret_shared(bits: [Boolean<F>; 8]) -> (Self, UInt8<F>) {
+        let unsigned = UInt8::from_bits_le(&bits);
+        let value = unsigned
+            .value()
+            .ok()
+            .map(|value| i8::from_ne_bytes([value]));
+        (Self { bits, value }, unsigned)
+    }
+
+    /// Reinterprets the byte as unsigned, together with whether that
+    /// preserves its value, i.e. whether the byte is non negative. No
+    /// constraints are added.
+    pub fn to_unsigned_checked(&self) -> Result<(UInt8<F>, Boolean<F>)> {
+        Ok((UInt8::from_bits_le(&self.bits), self.sign()?.not()))
+    }
 
-        let mut values = [None; 8];
-        if let Some(val) = value {
-            values
-                .iter_mut()
-                .enumerate()
-                .for_each(|(i, v)| *v = Some((val >> i) & 1 == 1));
+    /// Recomputes the cached `value` from the current assignment of the bits,
+    /// keeping it consistent after manipulating the bits directly.
+    pub fn refresh_value(&mut self) -> Result<(), SynthesisError> {
+        let mut value = 0_i8;
+        for (i, bit) in self.bits.iter().enumerate() {
+            value |= i8::from(bit.value()?) << i;
         }
+        self.value = Some(value);
+        Ok(())
+    }
+
+    /// Linear combination of the bits evaluating to the unsigned value of the
+    /// byte (`0..=255`) as a field element.
+    pub(crate) fn unsigned_value_lc(&self) -> LinearCombination<F> {
+        helpers::unsigned_lc(&self.bits)
+    }
+
+    /// Rotates the value left by `positions` bits. That is a right rotation
+    /// of the little-endian bits, and being a reindexing it adds no
+    /// constraints, unlike `BitManipulationGadget::rotate_left`.
+    pub(crate) fn rotate_left_free(&self, positions: usize) -> Result<Self> {
+        let mut bits = self.bits.clone();
+        bits.rotate_right(positions % 8);
+        Self::from_bits_le(&bits)
+    }
 
-        let mut bits = [Boolean::FALSE; 8];
-        for (b, v) in bits.iter_mut().zip(&values) {
-            *b = Boolean::new_variable(cs.clone(), || v.get(), mode)?;
+    /// Returns whether the byte is zero.
+    ///
+    /// Instead of OR-ing the eight negated bits, this uses the inverse trick.
+    /// Let `v` be the unsigned value of the bits as a field element, which is
+    /// zero iff every bit is zero. The prover supplies the flag `z` and
+    /// `inv = v^-1` (any value if `v` is zero) and we enforce:
+    ///
+    /// ```text
+    /// v * inv = 1 - z
+    /// v * z   = 0
+    /// ```
+    ///
+    /// If `v != 0` the second constraint forces `z = 0`, and if `v = 0` the
+    /// first one forces `z = 1`, so the two already make `z` boolean. This
+    /// still costs 3 constraints because `ark_r1cs_std` can only build a
+    /// `Boolean` from a variable through `AllocatedBool`, whose allocation
+    /// always adds a booleanity check.
+    pub fn is_zero(&self) -> Result<Boolean<F>> {
+        let cs = self.cs();
+        if cs.is_none() {
+            return Ok(Boolean::constant(self.value()? == 0));
         }
-        Ok(Self { bits, value })
+
+        let value_lc = self.unsigned_value_lc();
+        let is_zero = Boolean::new_witness(cs.clone(), || Ok(self.value()? == 0))?;
+        let inverse = cs.new_witness_variable(|| {
+            let value = F::from(u8::from_ne_bytes(self.value()?.to_ne_bytes()));
+            Ok(value.inverse().unwrap_or_else(F::zero))
+        })?;
+
+        cs.enforce_constraint(
+            value_lc.clone(),
+            lc!() + inverse,
+            lc!() + Variable::One - is_zero.lc(),
+        )?;
+        cs.enforce_constraint(value_lc, is_zero.lc(), lc!())?;
+
+        Ok(is_zero)
     }
-}
 
-impl<F: Field> R1CSVar<F> for Int8<F> {
-    type Value = i8;
+    /// Returns the multiplicative inverse of the byte modulo 256 and whether it
+    /// exists, which is the case iff the byte is odd. When it doesn't exist the
+    /// returned inverse is zero.
+    ///
+    /// With `x`, `inv` and `k` the unsigned values of the byte, the inverse and
+    /// an auxiliary quotient byte, we enforce `x * inv = valid + 256 * k`. As
+    /// both factors are below 256 the product can't wrap around the field.
+    pub fn mul_inverse_mod256(&self) -> Result<(Self, Boolean<F>)> {
+        let is_valid = self
+            .bits
+            .first()
+            .ok_or_else(|| anyhow!("Error getting the least significant bit"))?
+            .clone();
+        let cs = self.cs();
+
+        let unsigned_value =
+            || -> Result<u8, SynthesisError> { Ok(u8::from_ne_bytes(self.value()?.to_ne_bytes())) };
+        let unsigned_inverse = || -> Result<u8, SynthesisError> {
+            let value = unsigned_value()?;
+            if value % 2 == 0 {
+                return Ok(0);
+            }
+            // Newton's iteration: every odd byte is its own inverse modulo 8
+            // and each step doubles the number of correct low bits.
+            let mut inverse = value;
+            for _ in 0_u8..2_u8 {
+                inverse = inverse.wrapping_mul(2_u8.wrapping_sub(value.wrapping_mul(inverse)));
+            }
+            Ok(inverse)
+        };
+
+        if cs.is_none() {
+            let inverse = unsigned_inverse()?;
+            return Ok((Self::constant(i8::from_ne_bytes([inverse])), is_valid));
+        }
+
+        let inverse =
+            Self::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([unsigned_inverse()?])))?;
+        let quotient = Self::new_witness(cs.clone(), || {
+            let product = u16::from(unsigned_value()?) * u16::from(unsigned_inverse()?);
+            Ok(i8::from_ne_bytes([product.to_be_bytes()[0]]))
+        })?;
+
+        cs.enforce_constraint(
+            self.unsigned_value_lc(),
+            inverse.unsigned_value_lc(),
+            is_valid.lc() + quotient.unsigned_value_lc() * F::from(256_u64),
+        )?;
+        inverse.conditional_enforce_equal(&Self::constant(0), &is_valid.not())?;
 
-    fn cs(&self) -> ark_relations::r1cs::ConstraintSystemRef<F> {
-        self.bits.as_ref().cs()
+        Ok((inverse, is_valid))
     }
 
-    fn value(&self) -> Result<Self::Value, ark_relations::r1cs::SynthesisError> {
-        let mut value = None;
-        for (i, bit) in self.bits.iter().enumerate() {
-            let b = i8::from(bit.value()?);
-            value = match value {
-                Some(value) => Some(value + (b << i)),
-                None => Some(b << i),
+    /// Adds one to the byte when `cond` holds. The condition is fed as the
+    /// carry in of the adder, so no constant one nor select is needed. On
+    /// overflow (`i8::MAX + 1`) the result either wraps to `i8::MIN` or, if
+    /// `saturating` is set, stays at `i8::MAX`.
+    pub fn conditional_increment(&self, cond: &Boolean<F>, saturating: bool) -> Result<Self> {
+        let (sum, _) = helpers::ripple_carry_add(&self.bits, &[Boolean::FALSE; 8], cond.clone())?;
+        let result = Self::from_bits_le(&sum)?;
+        if !saturating {
+            return Ok(result);
+        }
+        // Only a non-negative byte becoming negative can overflow.
+        let overflow = self.sign()?.not().and(&result.sign()?)?;
+        Ok(Self::conditionally_select(
+            &overflow,
+            &Self::constant(i8::MAX),
+            &result,
+        )?)
+    }
+
+    /// Subtracts one from the byte when `cond` holds, by adding `-1` (all
+    /// bits set to `cond`). On overflow (`i8::MIN - 1`) the result either wraps
+    /// to `i8::MAX` or, if `saturating` is set, stays at `i8::MIN`.
+    pub fn conditional_decrement(&self, cond: &Boolean<F>, saturating: bool) -> Result<Self> {
+        let minus_one_or_zero = vec![cond.clone(); 8];
+        let (sum, _) = helpers::ripple_carry_add(&self.bits, &minus_one_or_zero, Boolean::FALSE)?;
+        let result = Self::from_bits_le(&sum)?;
+        if !saturating {
+            return Ok(result);
+        }
+        // Only a negative byte becoming non-negative can overflow.
+        let overflow = self.sign()?.and(&result.sign()?.not())?;
+        Ok(Self::conditionally_select(
+            &overflow,
+            &Self::constant(i8::MIN),
+            &result,
+        )?)
+    }
+
+    /// Returns the sign bit, which is set iff the byte is negative.
+    pub(crate) fn sign(&self) -> Result<Boolean<F>> {
+        self.bits
+            .last()
+            .cloned()
+            .ok_or_else(|| anyhow!("Error getting the sign bit"))
+    }
+
+    /// Computes `|self - other|` as an unsigned byte. Both operands are sign
+    /// extended to 9 bits before subtracting, so the difference never
+    /// overflows (not even for `i8::MIN - i8::MAX`).
+    pub fn abs_diff(&self, other: &Self) -> Result<UInt8<F>> {
+        let minuend = helpers::sign_extend(&self.bits, 9)?;
+        let negated_subtrahend = helpers::sign_extend(&other.bits, 9)?
+            .iter()
+            .map(Boolean::not)
+            .collect::<Vec<Boolean<F>>>();
+        // minuend - subtrahend = minuend + !subtrahend + 1
+        let (difference, _) =
+            helpers::ripple_carry_add(&minuend, &negated_subtrahend, Boolean::TRUE)?;
+
+        let is_negative = difference
+            .last()
+            .ok_or_else(|| anyhow!("Error getting the sign of the difference"))?
+            .clone();
+        // |difference| = (difference ^ sign) + sign
+        let flipped_difference = difference
+            .iter()
+            .map(|bit| bit.xor(&is_negative))
+            .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+        let (absolute_difference, _) =
+            helpers::ripple_carry_add(&flipped_difference, &[Boolean::FALSE; 9], is_negative)?;
+
+        let low_bits = absolute_difference
+            .get(..8)
+            .ok_or_else(|| anyhow!("Error getting the low bits of the difference"))?;
+        Ok(UInt8::from_bits_le(low_bits))
+    }
+
+    /// Returns whether the byte equals one of the `allowed` constants.
+    pub fn is_in_set(&self, allowed: &[i8]) -> Result<Boolean<F>> {
+        let mut is_allowed = Boolean::FALSE;
+        for value in allowed {
+            is_allowed = is_allowed.or(&self.is_eq(&Self::constant(*value))?)?;
+        }
+        Ok(is_allowed)
+    }
+
+    /// Enforces that the byte equals one of the `allowed` constants.
+    pub fn enforce_in_set(&self, allowed: &[i8]) -> Result<()> {
+        ensure!(!allowed.is_empty(), "The set of allowed values is empty");
+        self.is_in_set(allowed)?.enforce_equal(&Boolean::TRUE)?;
+        Ok(())
+    }
+
+    /// Enforces that the byte equals some element of `set`, ORing one
+    /// equality check per element. The cost is linear in the size of the set,
+    /// so large sets are better served by a sorted range or Merkle proof.
+    pub fn enforce_member_of(&self, set: &[Self]) -> Result<()> {
+        ensure!(!set.is_empty(), "The set is empty");
+        let is_member = set
+            .iter()
+            .map(|element| self.is_eq(element))
+            .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+        Boolean::kary_or(&is_member)?.enforce_equal(&Boolean::TRUE)?;
+        Ok(())
+    }
+
+    /// Returns whether `low <= self <= high`.
+    pub fn is_in_range(&self, low: i8, high: i8) -> Result<Boolean<F>> {
+        let is_below = self.is_lt(&Self::constant(low))?;
+        let is_above = Self::constant(high).is_lt(self)?;
+        Ok(is_below.or(&is_above)?.not())
+    }
+
+    /// Compares the bytes as signed integers, returning `-1`, `0` or `1` when
+    /// `self` is less than, equal to or greater than `other`, like `Ord::cmp`.
+    pub fn cmp(&self, other: &Self) -> Result<Self> {
+        let is_less = self.is_lt(other)?;
+        let is_greater = other.is_lt(self)?;
+        // -1 sets every bit and 1 only the lowest one.
+        let mut bits = vec![is_less.or(&is_greater)?];
+        bits.resize(8, is_less);
+        Self::from_bits_le(&bits)
+    }
+
+    /// Adds up `operands`, subtracting those flagged with `true`, wrapping on
+    /// overflow. Subtraction is folded into the same ripple carry adder as
+    /// `a + !b + 1`, so mixed sums like `a - b + c` cost the same as `addmany`.
+    pub fn addmany_signed(operands: &[(Self, bool)]) -> Result<Self> {
+        ensure!(!operands.is_empty(), "Cannot add an empty list of operands");
+        let mut sum = vec![Boolean::FALSE; 8];
+        for (operand, subtract) in operands {
+            let addend = if *subtract {
+                operand.bits.iter().map(Boolean::not).collect()
+            } else {
+                operand.bits.to_vec()
             };
+            (sum, _) = helpers::ripple_carry_add(&sum, &addend, Boolean::constant(*subtract))?;
         }
-        debug_assert_eq!(self.value, value);
-        value.get()
+        Self::from_bits_le(&sum)
     }
-}
 
-impl<F: Field> Int8<F> {
-    pub fn constant(value: i8) -> Self {
-        let mut bits = [Boolean::FALSE; 8];
-        let mut tmp = value;
-        for bit in &mut bits {
-            *bit = Boolean::constant((tmp & 1) == 1);
-            tmp >>= 1_i32;
+    /// Wrapping `addmany` that also reports the carry out, signed overflow
+    /// and zero flags of the sum. The operands are additionally added sign
+    /// extended to a width where the sum cannot overflow, so the flags account
+    /// for the whole sum rather than for a single step.
+    pub fn addmany_with_flags(operands: &[Self]) -> Result<(Self, AddFlags<F>)> {
+        let (first, rest) = operands
+            .split_first()
+            .ok_or_else(|| anyhow!("Cannot add an empty list of operands"))?;
+        let extra_bits = usize::try_from(usize::BITS - rest.len().leading_zeros())?;
+        let width = 8 + extra_bits;
+
+        let mut sum = first.bits.to_vec();
+        let mut carry_out = Boolean::FALSE;
+        let mut wide_sum = helpers::sign_extend(&first.bits, width)?;
+        for operand in rest {
+            let (partial_sum, carry) =
+                helpers::ripple_carry_add(&sum, &operand.bits, Boolean::FALSE)?;
+            sum = partial_sum;
+            // The unsigned sum exceeds a byte iff any of the steps carries.
+            carry_out = carry_out.or(&carry)?;
+            (wide_sum, _) = helpers::ripple_carry_add(
+                &wide_sum,
+                &helpers::sign_extend(&operand.bits, width)?,
+                Boolean::FALSE,
+            )?;
+        }
+
+        // The signed sum fits in an i8 iff its upper bits are a sign extension
+        // of bit 7.
+        let mut overflow = Boolean::FALSE;
+        if let Some((sign, upper_bits)) = wide_sum.get(7..).and_then(|bits| bits.split_first()) {
+            for bit in upper_bits {
+                overflow = overflow.or(&bit.xor(sign)?)?;
+            }
+        }
+
+        let result = Self::from_bits_le(&sum)?;
+        let zero = result.is_zero()?;
+        Ok((
+            result,
+            AddFlags {
+                carry_out,
+                overflow,
+                zero,
+            },
+        ))
+    }
+
+    /// Adds three bytes at once, bailing if the sum does not fit in an `i8`.
+    /// The operands are sign extended to 10 bits, enough for any sum of three
+    /// bytes (`3 * i8::MIN = -384`), and the upper bits of the sum are
+    /// constrained to be a sign extension of the result.
+    pub fn add3(a: &Self, b: &Self, c: &Self) -> Result<Self> {
+        if let (Ok(a_value), Ok(b_value), Ok(c_value)) = (a.value(), b.value(), c.value()) {
+            let sum = i16::from(a_value) + i16::from(b_value) + i16::from(c_value);
+            ensure!(i8::try_from(sum).is_ok(), "Addition overflow");
+        }
+
+        let (partial_sum, _) = helpers::ripple_carry_add(
+            &helpers::sign_extend(&a.bits, 10)?,
+            &helpers::sign_extend(&b.bits, 10)?,
+            Boolean::FALSE,
+        )?;
+        let (sum, _) = helpers::ripple_carry_add(
+            &partial_sum,
+            &helpers::sign_extend(&c.bits, 10)?,
+            Boolean::FALSE,
+        )?;
+
+        let (result_bits, upper_bits) = sum.split_at(8);
+        let result = Self::from_bits_le(result_bits)?;
+        let sign = result.sign()?;
+        for bit in upper_bits {
+            bit.enforce_equal(&sign)?;
         }
-        Self {
-            bits,
-            value: Some(value),
+        Ok(result)
+    }
+
+    /// Per-bit majority vote of three bytes, `(a & b) | (b & c) | (c & a)`,
+    /// e.g. for triple modular redundancy. Computed as `(a & b) ^ (c & (a ^ b))`
+    /// which takes two constraints per bit.
+    pub fn majority3(a: &Self, b: &Self, c: &Self) -> Result<Self> {
+        let mut bits = Vec::with_capacity(8);
+        for ((a_bit, b_bit), c_bit) in a.bits.iter().zip(&b.bits).zip(&c.bits) {
+            bits.push(a_bit.and(b_bit)?.xor(&c_bit.and(&a_bit.xor(b_bit)?)?)?);
         }
+        Self::from_bits_le(&bits)
     }
 
-    pub fn from_bits_le(bits: &[Boolean<F>]) -> Result<Self> {
-        assert_eq!(bits.len(), 8, "Invalid array length, should be 8");
-        let bits = <&[Boolean<F>; 8]>::try_from(bits)?.clone();
+    /// Returns `default` when the byte is zero and the byte itself otherwise.
+    pub fn or_default(&self, default: &Self) -> Result<Self> {
+        Ok(Self::conditionally_select(&self.is_zero()?, default, self)?)
+    }
 
-        let mut value = Some(0_i8);
-        for (i, b) in bits.iter().enumerate() {
-            value = match b.value().ok() {
-                Some(b) => value.map(|v| v + (i8::from(b) << i)),
-                None => None,
+    /// Enforces that the byte is a multiple of `align`, which must be a power
+    /// of two not above 256, by enforcing its low `log2(align)` bits are zero.
+    pub fn enforce_aligned(&self, align: u32) -> Result<()> {
+        ensure!(
+            align.is_power_of_two() && align <= 256,
+            "Alignment must be a power of two not above 256, got {align}"
+        );
+        let low_bits = usize::try_from(align.trailing_zeros())?;
+        for bit in self.bits.iter().take(low_bits) {
+            bit.enforce_equal(&Boolean::FALSE)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces that only bits allowed by `mask` are set, i.e. that
+    /// `self & !mask == 0`, by enforcing every other bit is false.
+    pub fn enforce_subset_of_mask(&self, mask: u8) -> Result<()> {
+        for (i, bit) in self.bits.iter().enumerate() {
+            if (mask >> i) & 1 == 0 {
+                bit.enforce_equal(&Boolean::FALSE)?;
             }
         }
+        Ok(())
+    }
 
-        Ok(Self { value, bits })
+    /// Negates the byte, saturating `-i8::MIN` to `i8::MAX` instead of
+    /// wrapping.
+    pub fn saturating_neg(&self) -> Result<Self> {
+        let negated_bits = self
+            .bits
+            .iter()
+            .map(Boolean::not)
+            .collect::<Vec<Boolean<F>>>();
+        // -x = !x + 1
+        let (negation, _) =
+            helpers::ripple_carry_add(&negated_bits, &[Boolean::FALSE; 8], Boolean::TRUE)?;
+        let negation = Self::from_bits_le(&negation)?;
+        // Only i8::MIN is negative both before and after negating, and its
+        // wrapped negation (0x80) flipped is i8::MAX (0x7f).
+        let is_min = self.sign()?.and(&negation.sign()?)?;
+        let saturated_bits = negation
+            .bits
+            .iter()
+            .map(|bit| bit.xor(&is_min))
+            .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+        Self::from_bits_le(&saturated_bits)
     }
-}
 
-impl<F: Field> ToBitsGadget<F> for Int8<F> {
-    fn to_bits_le(&self) -> Result<Vec<Boolean<F>>, SynthesisError> {
-        Ok(self.bits.to_vec())
+    /// Flags, from the most significant bit down, whether each bit is part of
+    /// the leading zeros.
+    fn leading_zero_flags(bits: &[Boolean<F>]) -> Result<Vec<Boolean<F>>> {
+        let mut seen_one = Boolean::FALSE;
+        let mut is_leading_zero = Vec::with_capacity(bits.len());
+        for bit in bits.iter().rev() {
+            seen_one = seen_one.or(bit)?;
+            is_leading_zero.push(seen_one.not());
+        }
+        Ok(is_leading_zero)
     }
-}
 
-impl<ConstraintF: Field> EqGadget<ConstraintF> for Int8<ConstraintF> {
-    #[tracing::instrument(target = "r1cs")]
-    fn is_eq(&self, other: &Self) -> Result<Boolean<ConstraintF>, SynthesisError> {
-        self.bits.as_ref().is_eq(&other.bits)
+    /// Counts the leading zeros of the byte's two's complement bits, from 0
+    /// for negative values up to 8 for zero.
+    pub fn leading_zeros(&self) -> Result<Self> {
+        Self::pack_bits(&helpers::count_ones(&Self::leading_zero_flags(
+            &self.bits,
+        )?)?)
     }
 
-    #[tracing::instrument(target = "r1cs")]
-    fn conditional_enforce_equal(
-        &self,
-        other: &Self,
-        condition: &Boolean<ConstraintF>,
-    ) -> Result<(), SynthesisError> {
-        self.bits.conditional_enforce_equal(&other.bits, condition)
+    /// Returns the minimum number of bits needed to represent the value in
+    /// two's complement, from 1 for `0` and `-1` up to 8.
+    pub fn min_bits(&self) -> Result<Self> {
+        let sign = self.sign()?;
+        // Flipping the bits of negative values turns their redundant sign bits
+        // into leading zeros, the sign bit itself always being one of them.
+        let flipped_bits = self
+            .bits
+            .iter()
+            .map(|bit| bit.xor(&sign))
+            .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+        // The bits below the redundant ones plus a single sign bit.
+        let mut needed_bits = Self::leading_zero_flags(&flipped_bits)?
+            .iter()
+            .map(Boolean::not)
+            .collect::<Vec<Boolean<F>>>();
+        needed_bits.push(Boolean::TRUE);
+        Self::pack_bits(&helpers::count_ones(&needed_bits)?)
     }
 
-    #[tracing::instrument(target = "r1cs")]
-    fn conditional_enforce_not_equal(
-        &self,
-        other: &Self,
-        condition: &Boolean<ConstraintF>,
-    ) -> Result<(), SynthesisError> {
-        self.bits
-            .conditional_enforce_not_equal(&other.bits, condition)
+    /// Returns `floor(log2(self))` and whether it is defined, i.e. whether
+    /// the byte is positive. Non-positive bytes yield zero.
+    pub fn ilog2(&self) -> Result<(Self, Boolean<F>)> {
+        let leading_zeros = self.leading_zeros()?;
+        let [lz0, lz1, lz2, zero_byte, _, _, _, _] = leading_zeros.bits.clone();
+        // Positive bytes have between 1 and 7 leading zeros, so the 4th bit of
+        // the count is only set for zero, which has 8.
+        let is_positive = self.sign()?.not().and(&zero_byte.not())?;
+        // For a 3-bit `x`, `7 - x` is just `!x`.
+        let log_bits = [lz0, lz1, lz2]
+            .iter()
+            .map(|bit| bit.not().and(&is_positive))
+            .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+        Ok((Self::pack_bits(&log_bits)?, is_positive))
     }
-}
 
-impl<ConstraintF: Field> CondSelectGadget<ConstraintF> for Int8<ConstraintF> {
-    #[tracing::instrument(target = "r1cs", skip(cond, true_value, false_value))]
-    fn conditionally_select(
-        cond: &Boolean<ConstraintF>,
-        true_value: &Self,
-        false_value: &Self,
-    ) -> Result<Self, SynthesisError> {
-        let selected_bits = true_value
+    /// Enforces that the byte, read as unsigned, is a valid discriminant of
+    /// an enum with `variant_count` variants, i.e. is in `0..variant_count`.
+    pub fn enforce_valid_discriminant(&self, variant_count: u8) -> Result<()> {
+        ensure!(
+            variant_count > 0,
+            "An enum without variants has no valid discriminant"
+        );
+        let variant_count_bits = helpers::constant_bits_le(u64::from(variant_count), 8);
+        helpers::is_less_than_unsigned(&self.bits, &variant_count_bits)?
+            .enforce_equal(&Boolean::TRUE)?;
+        Ok(())
+    }
+
+    /// Returns the byte if `keep` is true and zero otherwise, ANDing every bit
+    /// with `keep`.
+    pub fn mask(&self, keep: &Boolean<F>) -> Result<Self> {
+        let masked_bits = self
             .bits
             .iter()
-            .zip(&false_value.bits)
-            .map(|(t, f)| cond.select(t, f));
-        let mut bits = [Boolean::FALSE; 8];
-        for (result, new) in bits.iter_mut().zip(selected_bits) {
-            *result = new?;
+            .map(|bit| bit.and(keep))
+            .collect::<Result<Vec<Boolean<F>>, SynthesisError>>()?;
+        Self::from_bits_le(&masked_bits)
+    }
+
+    /// Reverses the order of the bits within each nibble, leaving the nibbles
+    /// in place. No constraints are added.
+    pub fn reverse_nibble_bits(&self) -> Result<Self> {
+        let [b0, b1, b2, b3, b4, b5, b6, b7] = self.bits.clone();
+        Self::from_bits_le(&[b3, b2, b1, b0, b7, b6, b5, b4])
+    }
+
+    /// Enforces that the bits evaluate to the cached value, catching bits and
+    /// value that got out of sync. The value must be known, so this is meant
+    /// as a development check rather than part of a circuit's shape.
+    pub fn enforce_value_consistency(&self) -> Result<()> {
+        let value = self
+            .value
+            .ok_or_else(|| anyhow!("Cannot check the consistency of an unknown value"))?;
+        let unsigned_value = u8::from_ne_bytes(value.to_ne_bytes());
+
+        let cs = self.cs();
+        if cs.is_none() {
+            ensure!(
+                helpers::unsigned_value(&self.bits)? == u64::from(unsigned_value),
+                "The bits don't match the cached value {value}"
+            );
+            return Ok(());
         }
 
-        let value = cond.value().ok().and_then(|cond| {
-            if cond {
-                true_value.value().ok()
-            } else {
-                false_value.value().ok()
-            }
-        });
-        Ok(Self { bits, value })
+        cs.enforce_constraint(
+            lc!() + Variable::One,
+            self.unsigned_value_lc(),
+            lc!() + (F::from(unsigned_value), Variable::One),
+        )?;
+        Ok(())
+    }
+
+    /// Returns the remainder of dividing the byte, read as unsigned, by the
+    /// constant `m`. Errors if `m` is zero.
+    pub fn rem_constant(&self, m: u8) -> Result<Self> {
+        let (_, remainder) = helpers::unsigned_div_rem_constant(&self.bits, m)?;
+        Self::from_bits_le(&remainder)
+    }
+
+    /// Returns the quotient of dividing the byte, read as unsigned, by the
+    /// constant `d`, rounding down. Errors if `d` is zero.
+    pub fn div_constant(&self, d: u8) -> Result<Self> {
+        let (quotient, _) = helpers::unsigned_div_rem_constant(&self.bits, d)?;
+        Self::from_bits_le(&quotient)
+    }
+
+    /// Returns `ceil(self / d)` for a non negative byte, which is enforced.
+    /// Errors if `d` is zero.
+    pub fn div_ceil_constant(&self, d: u8) -> Result<Self> {
+        self.sign()?.enforce_equal(&Boolean::FALSE)?;
+        // Same as `div_constant` and `rem_constant` but sharing the witnesses.
+        let (quotient, remainder) = helpers::unsigned_div_rem_constant(&self.bits, d)?;
+        // The increment can't overflow: a remainder requires `d >= 2`, which
+        // keeps the quotient below 64.
+        Self::from_bits_le(&quotient)?.conditional_increment(&Boolean::kary_or(&remainder)?, false)
+    }
+}
+
+impl<F: PrimeField> Int8<F> {
+    /// Returns the lowest 8 bits of `elem` as an Int8, e.g. to derive bytes
+    /// from a field valued hash. The element is fully decomposed into bits
+    /// but nothing is enforced about the discarded high bits.
+    pub fn from_field_low_byte(elem: &FpVar<F>) -> Result<Self> {
+        let bits = elem.to_bits_le()?;
+        let low_bits = bits
+            .get(..8)
+            .ok_or_else(|| anyhow!("The field element has less than 8 bits"))?;
+        Self::from_bits_le(low_bits)
     }
 }
 
@@ -541,3 +1071,946 @@ impl<F: Field> BitManipulationGadget<F> for Int8<F> {
         Ok(shifted_value)
     }
 }
+
+#[cfg(test)]
+mod int8_tests {
+    use super::ByteSource;
+    use crate::gadgets::{ConstraintF, Int8Gadget};
+    use ark_r1cs_std::{
+        fields::fp::FpVar,
+        prelude::{AllocVar, Boolean},
+        R1CSVar, ToBitsGadget,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_abs_diff() {
+        let pairs = [
+            (5_i8, 3_i8),
+            (3, 5),
+            (-7, 7),
+            (0, 0),
+            (i8::MIN, i8::MAX),
+            (i8::MAX, i8::MIN),
+            (i8::MIN, i8::MIN),
+            (-1, i8::MIN),
+        ];
+
+        for (left, right) in pairs {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let left_var = Int8Gadget::new_witness(cs.clone(), || Ok(left)).unwrap();
+            let right_var = Int8Gadget::new_witness(cs.clone(), || Ok(right)).unwrap();
+
+            let result = left_var.abs_diff(&right_var).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(left.abs_diff(right), result.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_new_witness_vec_from_hex() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+
+        let bytes = Int8Gadget::new_witness_vec_from_hex(cs.clone(), "deadbeef").unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(vec![-34_i8, -83, -66, -17], bytes.value().unwrap());
+    }
+
+    #[test]
+    fn test_new_witness_vec_from_u8() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+
+        let bytes =
+            Int8Gadget::new_witness_vec_from_u8(cs.clone(), &[0, 1, 127, 128, 255]).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(vec![0_i8, 1, 127, -128, -1], bytes.value().unwrap());
+    }
+
+    #[test]
+    fn test_new_witness_vec_from_hex_with_odd_length_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+
+        assert!(Int8Gadget::new_witness_vec_from_hex(cs, "abc").is_err());
+    }
+
+    #[test]
+    fn test_new_witness_vec_from_hex_with_invalid_characters_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+
+        assert!(Int8Gadget::new_witness_vec_from_hex(cs, "zz01").is_err());
+    }
+
+    #[test]
+    fn test_pack_three_bits() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bits =
+            Vec::<Boolean<ConstraintF>>::new_witness(cs.clone(), || Ok(vec![true, false, true]))
+                .unwrap();
+
+        let result = Int8Gadget::pack_bits(&bits).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(0b101, result.value().unwrap());
+    }
+
+    #[test]
+    fn test_pack_eight_bits() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bits = Vec::<Boolean<ConstraintF>>::new_witness(cs.clone(), || {
+            Ok(vec![true, false, false, false, false, false, false, true])
+        })
+        .unwrap();
+
+        let result = Int8Gadget::pack_bits(&bits).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(-127, result.value().unwrap());
+    }
+
+    #[test]
+    fn test_pack_nine_bits_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bits = Vec::<Boolean<ConstraintF>>::new_witness(cs, || Ok(vec![false; 9])).unwrap();
+
+        assert!(Int8Gadget::pack_bits(&bits).is_err());
+    }
+
+    #[test]
+    fn test_reinterpret_shared() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let bits = Vec::<Boolean<ConstraintF>>::new_witness(cs.clone(), || {
+            Ok(vec![true, true, true, true, true, true, true, true])
+        })
+        .unwrap();
+        let num_witness_variables = cs.num_witness_variables();
+        let num_constraints = cs.num_constraints();
+
+        let (signed, unsigned) =
+            Int8Gadget::reinterpret_shared(<[Boolean<ConstraintF>; 8]>::try_from(bits).unwrap());
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(num_witness_variables, cs.num_witness_variables());
+        assert_eq!(num_constraints, cs.num_constraints());
+        for (signed_bit, unsigned_bit) in signed
+            .to_bits_le()
+            .unwrap()
+            .iter()
+            .zip(unsigned.to_bits_le().unwrap())
+        {
+            assert_eq!(signed_bit.lc(), unsigned_bit.lc());
+        }
+        assert_eq!(-1, signed.value().unwrap());
+        assert_eq!(u8::MAX, unsigned.value().unwrap());
+    }
+
+    #[test]
+    fn test_is_zero() {
+        for (value, expected) in [(0_i8, true), (1, false), (-1, false), (i8::MIN, false)] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            let is_zero = byte.is_zero().unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, is_zero.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_rotate_left_free() {
+        for positions in 0..10 {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(-75)).unwrap();
+            let num_constraints = cs.num_constraints();
+
+            let rotated = byte.rotate_left_free(positions).unwrap();
+
+            assert_eq!(
+                (-75_i8).rotate_left(u32::try_from(positions).unwrap()),
+                rotated.value().unwrap()
+            );
+            assert_eq!(num_constraints, cs.num_constraints());
+        }
+    }
+
+    #[test]
+    fn test_is_zero_constraint_count() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let byte = Int8Gadget::new_witness(cs.clone(), || Ok(42)).unwrap();
+        let num_constraints = cs.num_constraints();
+
+        let is_zero = byte.is_zero().unwrap();
+
+        assert!(!is_zero.value().unwrap());
+        assert_eq!(num_constraints + 3, cs.num_constraints());
+    }
+
+    #[test]
+    fn test_is_zero_of_a_constant_adds_no_constraints() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+
+        let is_zero = Int8Gadget::constant(0).is_zero().unwrap();
+
+        assert!(is_zero.value().unwrap());
+        assert_eq!(0, cs.num_constraints());
+    }
+
+    #[test]
+    fn test_refresh_value_after_editing_the_bits() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let mut byte = Int8Gadget::new_witness(cs.clone(), || Ok(4)).unwrap();
+        *byte.bits.get_mut(0).unwrap() = Boolean::TRUE;
+        *byte.bits.get_mut(7).unwrap() = Boolean::new_witness(cs.clone(), || Ok(true)).unwrap();
+
+        byte.refresh_value().unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(Some(-123), byte.value);
+        assert_eq!(-123, byte.value().unwrap());
+    }
+
+    #[test]
+    fn test_bits_accessor() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let byte = Int8Gadget::new_witness(cs, || Ok(0b0100_0101)).unwrap();
+
+        let bits = byte
+            .bits()
+            .iter()
+            .map(|bit| bit.value().unwrap())
+            .collect::<Vec<bool>>();
+
+        assert_eq!(
+            vec![true, false, true, false, false, false, true, false],
+            bits
+        );
+    }
+
+    #[test]
+    fn test_mul_inverse_mod256_of_odd_bytes() {
+        for value in [1_i8, 3, -1, 127, -127, i8::MIN + 1] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            let (inverse, is_valid) = byte.mul_inverse_mod256().unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert!(is_valid.value().unwrap());
+            assert_eq!(1, value.wrapping_mul(inverse.value().unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_mul_inverse_mod256_of_even_bytes() {
+        for value in [0_i8, 2, 4, -2, i8::MIN] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            let (inverse, is_valid) = byte.mul_inverse_mod256().unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert!(!is_valid.value().unwrap());
+            assert_eq!(0, inverse.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_conditional_increment() {
+        let cases = [
+            (5_i8, true, false, 6_i8),
+            (5, false, false, 5),
+            (-1, true, false, 0),
+            (i8::MAX, true, false, i8::MIN),
+            (i8::MAX, true, true, i8::MAX),
+            (i8::MAX, false, true, i8::MAX),
+            (126, true, true, i8::MAX),
+        ];
+        for (value, condition, saturating, expected) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let cond = Boolean::new_witness(cs.clone(), || Ok(condition)).unwrap();
+
+            let result = byte.conditional_increment(&cond, saturating).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, result.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_conditional_decrement() {
+        let cases = [
+            (5_i8, true, false, 4_i8),
+            (5, false, false, 5),
+            (0, true, false, -1),
+            (i8::MIN, true, false, i8::MAX),
+            (i8::MIN, true, true, i8::MIN),
+            (i8::MIN, false, true, i8::MIN),
+            (-127, true, true, i8::MIN),
+        ];
+        for (value, condition, saturating, expected) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let cond = Boolean::new_witness(cs.clone(), || Ok(condition)).unwrap();
+
+            let result = byte.conditional_decrement(&cond, saturating).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, result.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_in_set() {
+        for (value, is_allowed) in [(3_i8, true), (-128, true), (4, false), (0, false)] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            byte.enforce_in_set(&[1, 3, -128, 7]).unwrap();
+
+            assert_eq!(is_allowed, cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_in_set_with_an_empty_set_fails() {
+        let byte = Int8Gadget::constant(1);
+
+        assert!(byte.enforce_in_set(&[]).is_err());
+    }
+
+    #[test]
+    fn test_addmany_signed() {
+        let cases = [
+            (vec![(5_i8, false), (3, true), (10, false)], 12_i8),
+            (vec![(5_i8, true)], -5),
+            (vec![(-128_i8, true)], -128),
+            (vec![(0_i8, false), (1, true), (1, true)], -2),
+            (vec![(127_i8, false), (1, false), (-1, true)], -127),
+            (vec![(-100_i8, false), (100, true), (-100, true)], -100),
+        ];
+        for (primitive_operands, expected) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let operands = primitive_operands
+                .iter()
+                .map(|(value, subtract)| {
+                    (
+                        Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap(),
+                        *subtract,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let result = Int8Gadget::addmany_signed(&operands).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, result.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_addmany_signed_without_operands_fails() {
+        assert!(Int8Gadget::addmany_signed(&[]).is_err());
+    }
+
+    #[test]
+    fn test_addmany_with_flags() {
+        // (operands, result, carry_out, overflow, zero)
+        let cases = [
+            (vec![1_i8, 2], 3_i8, false, false, false),
+            (vec![127_i8, 1], -128, false, true, false),
+            (vec![-1_i8, 1], 0, true, false, true),
+            (vec![-128_i8, -128], 0, true, true, true),
+            (vec![-1_i8, -1], -2, true, false, false),
+            (vec![127_i8, 1, -1], 127, true, false, false),
+            (vec![100_i8, 100, -100, -100], 0, true, false, true),
+            (vec![0_i8], 0, false, false, true),
+        ];
+        for (primitive_operands, expected, carry_out, overflow, zero) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let operands = Int8Gadget::new_witness_vec(cs.clone(), &primitive_operands).unwrap();
+
+            let (result, flags) = Int8Gadget::addmany_with_flags(&operands).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, result.value().unwrap());
+            assert_eq!(carry_out, flags.carry_out.value().unwrap());
+            assert_eq!(overflow, flags.overflow.value().unwrap());
+            assert_eq!(zero, flags.zero.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_is_in_range() {
+        let cases = [
+            (5_i8, -3_i8, 5_i8, true),
+            (-3, -3, 5, true),
+            (0, -3, 5, true),
+            (6, -3, 5, false),
+            (-4, -3, 5, false),
+            (i8::MIN, i8::MIN, i8::MAX, true),
+            (0, 1, -1, false),
+        ];
+        for (value, low, high, expected) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            let is_in_range = byte.is_in_range(low, high).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, is_in_range.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_random_witness_is_reproducible() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut same_rng = StdRng::seed_from_u64(42);
+
+        let bytes = (0_u8..32)
+            .map(|_| Int8Gadget::random_witness(cs.clone(), &mut rng).unwrap())
+            .collect::<Vec<Int8Gadget>>();
+        let same_bytes = (0_u8..32)
+            .map(|_| Int8Gadget::random_witness(cs.clone(), &mut same_rng).unwrap())
+            .collect::<Vec<Int8Gadget>>();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(bytes.value().unwrap(), same_bytes.value().unwrap());
+        assert!(!bytes.is_constant());
+    }
+
+    #[test]
+    fn test_add3() {
+        let cases = [
+            (1_i8, 2_i8, 3_i8),
+            (127, 127, -127),
+            (-128, 127, 0),
+            (-100, -28, 0),
+            (50, 50, 27),
+            (-43, -43, -42),
+        ];
+        for (a, b, c) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let [a_var, b_var, c_var] =
+                [a, b, c].map(|value| Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap());
+
+            let sum = Int8Gadget::add3(&a_var, &b_var, &c_var).unwrap();
+
+            let expected = i8::try_from(i16::from(a) + i16::from(b) + i16::from(c)).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, sum.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_add3_with_overflow_fails() {
+        let cases = [
+            (127_i8, 1_i8, 0_i8),
+            (100, 100, -72),
+            (-128, -1, 0),
+            (-50, -50, -50),
+        ];
+        for (a, b, c) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let [a_var, b_var, c_var] =
+                [a, b, c].map(|value| Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap());
+
+            assert!(Int8Gadget::add3(&a_var, &b_var, &c_var).is_err());
+        }
+    }
+
+    #[test]
+    fn test_majority3() {
+        let cases = [
+            (0_i8, 0_i8, 0_i8),
+            (-1, 0, -1),
+            (0b0101_0101, 0b0011_0011, 0b0000_1111),
+            (-128, 127, -86),
+            (42, 42, -42),
+        ];
+        for (a, b, c) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let [a_var, b_var, c_var] =
+                [a, b, c].map(|value| Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap());
+
+            let majority = Int8Gadget::majority3(&a_var, &b_var, &c_var).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!((a & b) | (b & c) | (c & a), majority.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_or_default() {
+        for (value, expected) in [(0_i8, 42_i8), (7, 7), (-128, -128)] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let default = Int8Gadget::new_witness(cs.clone(), || Ok(42)).unwrap();
+
+            let result = byte.or_default(&default).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, result.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_aligned() {
+        // (value, align, is aligned)
+        let cases = [
+            (16_i8, 16_u32, true),
+            (-32, 16, true),
+            (-128, 128, true),
+            (0, 256, true),
+            (7, 1, true),
+            (24, 16, false),
+            (-1, 2, false),
+            (64, 128, false),
+            (1, 256, false),
+        ];
+        for (value, align, is_aligned) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            byte.enforce_aligned(align).unwrap();
+
+            assert_eq!(is_aligned, cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_aligned_with_an_invalid_alignment_fails() {
+        let byte = Int8Gadget::constant(0);
+
+        for align in [0_u32, 3, 12, 512] {
+            assert!(byte.enforce_aligned(align).is_err());
+        }
+    }
+
+    #[test]
+    fn test_saturating_neg() {
+        for value in [-128_i8, -127, -1, 0, 1, 42, 127] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            let negation = byte.saturating_neg().unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(value.saturating_neg(), negation.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_from_field_low_byte() {
+        for (value, expected) in [
+            (0x1234_5678_9abc_def0_u64, 0xf0_u8),
+            (0x1ff, 0xff),
+            (0x7f, 0x7f),
+            (0x100, 0x00),
+        ] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let elem = FpVar::new_witness(cs.clone(), || Ok(ConstraintF::from(value))).unwrap();
+
+            let byte = Int8Gadget::from_field_low_byte(&elem).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(i8::from_ne_bytes([expected]), byte.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        for value in [0_i8, 1, 2, 3, 64, 127, -1, -128] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            let leading_zeros = byte.leading_zeros().unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(
+                i8::try_from(value.leading_zeros()).unwrap(),
+                leading_zeros.value().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_leading_zeros_of_a_constant() {
+        let leading_zeros = Int8Gadget::constant(5).leading_zeros().unwrap();
+
+        assert!(leading_zeros.is_constant());
+        assert_eq!(5, leading_zeros.value().unwrap());
+    }
+
+    #[test]
+    fn test_ilog2() {
+        // 0xff is -1, so like every non-positive byte it has no logarithm.
+        let cases = [
+            (1_i8, Some(0_i8)),
+            (2, Some(1)),
+            (3, Some(1)),
+            (100, Some(6)),
+            (127, Some(6)),
+            (i8::from_ne_bytes([0xff]), None),
+            (0, None),
+            (-5, None),
+            (-128, None),
+        ];
+        for (value, expected) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            let (log, is_valid) = byte.ilog2().unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected.is_some(), is_valid.value().unwrap());
+            assert_eq!(expected.unwrap_or(0), log.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_valid_discriminant() {
+        // (discriminant, variant count, is valid)
+        let cases = [
+            (0_u8, 1_u8, true),
+            (2, 3, true),
+            (3, 3, false),
+            (200, 255, true),
+            (254, 255, true),
+            (255, 255, false),
+            (128, 4, false),
+        ];
+        for (discriminant, variant_count, is_valid) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte =
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([discriminant])))
+                    .unwrap();
+
+            byte.enforce_valid_discriminant(variant_count).unwrap();
+
+            assert_eq!(is_valid, cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_valid_discriminant_without_variants_fails() {
+        assert!(Int8Gadget::constant(0)
+            .enforce_valid_discriminant(0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_mask() {
+        for (value, keep, expected) in [(-93_i8, true, -93_i8), (-93, false, 0), (0, true, 0)] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let keep = Boolean::new_witness(cs.clone(), || Ok(keep)).unwrap();
+
+            let masked = byte.mask(&keep).unwrap();
+
+            assert_eq!(expected, masked.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_value_consistency() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let byte = Int8Gadget::new_witness(cs.clone(), || Ok(-77)).unwrap();
+
+        byte.enforce_value_consistency().unwrap();
+        Int8Gadget::constant(-77)
+            .enforce_value_consistency()
+            .unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_value_consistency_with_tampered_value_fails() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let mut byte = Int8Gadget::new_witness(cs.clone(), || Ok(3)).unwrap();
+        byte.value = Some(5);
+
+        byte.enforce_value_consistency().unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+
+        let mut constant = Int8Gadget::constant(3);
+        constant.value = Some(5);
+        assert!(constant.enforce_value_consistency().is_err());
+    }
+
+    #[test]
+    fn test_min_bits() {
+        let cases = [
+            (0_i8, 1_i8),
+            (1, 2),
+            (-1, 1),
+            (-2, 2),
+            (5, 4),
+            (-5, 4),
+            (64, 8),
+            (-64, 7),
+            (127, 8),
+            (-128, 8),
+        ];
+        for (value, expected) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+
+            let min_bits = byte.min_bits().unwrap();
+
+            assert_eq!(expected, min_bits.value().unwrap());
+            assert_eq!(
+                expected,
+                Int8Gadget::constant(value)
+                    .min_bits()
+                    .unwrap()
+                    .value()
+                    .unwrap()
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_rem_constant() {
+        for m in [1_u8, 2, 3, 7, 10, 16, 100, 200, 255] {
+            for value in [0_u8, 1, 9, 99, 127, 128, 200, 254, 255] {
+                let cs = ConstraintSystem::<ConstraintF>::new_ref();
+                let byte =
+                    Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([value]))).unwrap();
+
+                let remainder = byte.rem_constant(m).unwrap();
+
+                assert_eq!(
+                    value % m,
+                    u8::from_ne_bytes(remainder.value().unwrap().to_ne_bytes())
+                );
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rem_constant_by_zero_fails() {
+        assert!(Int8Gadget::constant(5).rem_constant(0).is_err());
+    }
+
+    #[test]
+    fn test_div_constant() {
+        for d in [1_u8, 2, 3, 7, 10, 16, 100, 200, 255] {
+            for value in [0_u8, 1, 9, 99, 127, 128, 200, 254, 255] {
+                let cs = ConstraintSystem::<ConstraintF>::new_ref();
+                let byte =
+                    Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([value]))).unwrap();
+
+                let quotient = byte.div_constant(d).unwrap();
+
+                assert_eq!(
+                    value / d,
+                    u8::from_ne_bytes(quotient.value().unwrap().to_ne_bytes())
+                );
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_constant_by_zero_fails() {
+        assert!(Int8Gadget::constant(5).div_constant(0).is_err());
+    }
+
+    #[test]
+    fn test_enforce_subset_of_mask() {
+        // (flags, mask, is compliant)
+        let cases = [
+            (0b0000_0101_u8, 0b0000_0111_u8, true),
+            (0, 0, true),
+            (0b1000_0001, 0b1000_0001, true),
+            (0b0000_1001, 0b0000_0111, false),
+            (0b1000_0000, 0b0111_1111, false),
+        ];
+        for (flags, mask, is_compliant) in cases {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte =
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([flags]))).unwrap();
+
+            byte.enforce_subset_of_mask(mask).unwrap();
+
+            assert_eq!(is_compliant, cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_reverse_nibble_bits() {
+        let reverse_nibble_bits =
+            |byte: u8| ((byte & 0x0f).reverse_bits() >> 4_u32) | ((byte >> 4_u32).reverse_bits());
+        for value in [0_u8, 0x01, 0x12, 0x80, 0xa5, 0x3c, 0xff, 0xe1] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte =
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([value]))).unwrap();
+            let num_constraints = cs.num_constraints();
+
+            let reversed = byte.reverse_nibble_bits().unwrap();
+
+            assert_eq!(
+                reverse_nibble_bits(value),
+                u8::from_ne_bytes(reversed.value().unwrap().to_ne_bytes())
+            );
+            assert_eq!(num_constraints, cs.num_constraints());
+        }
+    }
+
+    #[test]
+    fn test_enforce_member_of() {
+        let set = [12_i8, -7, 0, 127, -7];
+        for (value, is_member) in [
+            (-7_i8, true),
+            (127, true),
+            (12, true),
+            (13, false),
+            (-128, false),
+        ] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let set = Int8Gadget::new_witness_vec(cs.clone(), &set).unwrap();
+
+            byte.enforce_member_of(&set).unwrap();
+
+            assert_eq!(is_member, cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_member_of_an_empty_set_fails() {
+        assert!(Int8Gadget::constant(0).enforce_member_of(&[]).is_err());
+    }
+
+    #[test]
+    fn test_new_mixed_vec() {
+        let items = [
+            ByteSource::Constant(0x7f),
+            ByteSource::Witness(-3),
+            ByteSource::Constant(0x7f),
+            ByteSource::Constant(-128),
+            ByteSource::Witness(42),
+        ];
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let witnesses_only_cs = ConstraintSystem::<ConstraintF>::new_ref();
+        Int8Gadget::new_witness_vec(witnesses_only_cs.clone(), &[-3_i8, 42]).unwrap();
+
+        let bytes = Int8Gadget::new_mixed_vec(cs.clone(), &items).unwrap();
+
+        assert_eq!(vec![0x7f_i8, -3, 0x7f, -128, 42], bytes.value().unwrap());
+        assert_eq!(
+            [true, false, true, true, false].to_vec(),
+            bytes
+                .iter()
+                .map(|byte| byte.bits.iter().all(Boolean::is_constant))
+                .collect::<Vec<bool>>()
+        );
+        assert_eq!(witnesses_only_cs.num_constraints(), cs.num_constraints());
+        assert_eq!(
+            witnesses_only_cs.num_witness_variables(),
+            cs.num_witness_variables()
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "The cached value doesn't match the bits")]
+    fn test_value_with_a_stale_cached_value_panics() {
+        let mut byte = Int8Gadget::constant(3);
+        byte.value = Some(5);
+
+        let _ = byte.value();
+    }
+
+    #[test]
+    fn test_cmp() {
+        let pairs = [
+            (-5_i8, 3_i8),
+            (3, 3),
+            (3, -5),
+            (-128, 127),
+            (127, -128),
+            (0, 0),
+        ];
+        for (left, right) in pairs {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let left_byte = Int8Gadget::new_witness(cs.clone(), || Ok(left)).unwrap();
+            let right_byte = Int8Gadget::new_witness(cs.clone(), || Ok(right)).unwrap();
+
+            let ordering = left_byte.cmp(&right_byte).unwrap();
+
+            let expected = match left.cmp(&right) {
+                Ordering::Less => -1,
+                Ordering::Equal => 0,
+                Ordering::Greater => 1,
+            };
+            assert_eq!(expected, ordering.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_to_unsigned_checked() {
+        for (value, is_lossless) in [
+            (0_i8, true),
+            (1, true),
+            (127, true),
+            (-1, false),
+            (-128, false),
+        ] {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let num_constraints = cs.num_constraints();
+
+            let (unsigned, is_value_preserving) = byte.to_unsigned_checked().unwrap();
+
+            assert_eq!(value.to_ne_bytes()[0], unsigned.value().unwrap());
+            assert_eq!(is_lossless, is_value_preserving.value().unwrap());
+            assert_eq!(num_constraints, cs.num_constraints());
+        }
+    }
+
+    #[test]
+    fn test_div_ceil_constant() {
+        for d in [1_u8, 2, 3, 7, 10, 64, 127, 128, 255] {
+            for value in [0_i8, 1, 6, 7, 21, 64, 100, 126, 127] {
+                let cs = ConstraintSystem::<ConstraintF>::new_ref();
+                let byte = Int8Gadget::new_witness(cs.clone(), || Ok(value)).unwrap();
+                let unsigned_value = u8::try_from(value).unwrap();
+                let expected = unsigned_value / d + u8::from(unsigned_value % d != 0);
+
+                let quotient = byte.div_ceil_constant(d).unwrap();
+
+                assert_eq!(i8::try_from(expected).unwrap(), quotient.value().unwrap());
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_ceil_constant_of_a_negative_byte_is_not_satisfied() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let byte = Int8Gadget::new_witness(cs.clone(), || Ok(-6)).unwrap();
+
+        byte.div_ceil_constant(3).unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_div_ceil_constant_by_zero_fails() {
+        assert!(Int8Gadget::constant(5).div_ceil_constant(0).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+/// Implements the boilerplate shared by the signed integer gadgets: `$int<F>`
+/// must be a struct with little-endian `bits: [Boolean<F>; $bits]` and a
+/// cached `value: Option<$native>`.
+macro_rules! impl_signed_int {
+    ($int:ident, $native:ty, $bits:literal) => {
+        const _: () = {
+            use std::borrow::Borrow;
+
+            use anyhow::anyhow;
+            use ark_ff::Field;
+            use ark_r1cs_std::{
+                prelude::{AllocVar, AllocationMode, Boolean, EqGadget},
+                select::CondSelectGadget,
+                uint8::UInt8,
+                Assignment, R1CSVar, ToBitsGadget, ToBytesGadget,
+            };
+            use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+
+            const ONE: $native = 1;
+            use $crate::gadgets::{
+                helpers,
+                traits::{IntGadget, IsWitness},
+            };
+
+            impl<F: Field> IsWitness<F> for $int<F> {}
+
+            impl<F: Field> ToBytesGadget<F> for $int<F> {
+                fn to_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+                    Ok(self.bits.chunks(8).map(UInt8::from_bits_le).collect())
+                }
+            }
+
+            impl<F: Field> AllocVar<$native, F> for $int<F> {
+                fn new_variable<T: Borrow<$native>>(
+                    cs: impl Into<Namespace<F>>,
+                    f: impl FnOnce() -> Result<T, SynthesisError>,
+                    mode: AllocationMode,
+                ) -> Result<Self, SynthesisError> {
+                    let ns = cs.into();
+                    let cs = ns.cs();
+                    let value = f().map(|f| *f.borrow()).ok();
+
+                    let mut values = [None; $bits];
+                    if let Some(val) = value {
+                        values
+                            .iter_mut()
+                            .enumerate()
+                            .for_each(|(i, v)| *v = Some((val >> i) & ONE == ONE));
+                    }
+
+                    let mut bits = [Boolean::FALSE; $bits];
+                    for (b, v) in bits.iter_mut().zip(&values) {
+                        *b = Boolean::new_variable(cs.clone(), || v.get(), mode)?;
+                    }
+                    Ok(Self { bits, value })
+                }
+            }
+
+            impl<F: Field> R1CSVar<F> for $int<F> {
+                type Value = $native;
+
+                fn cs(&self) -> ConstraintSystemRef<F> {
+                    self.bits.as_ref().cs()
+                }
+
+                fn value(&self) -> Result<Self::Value, SynthesisError> {
+                    let mut value: $native = 0;
+                    for (i, bit) in self.bits.iter().enumerate() {
+                        value |= <$native>::from(bit.value()?) << i;
+                    }
+                    #[cfg(any(test, feature = "strict-value-checks"))]
+                    assert_eq!(
+                        self.value,
+                        Some(value),
+                        "The cached value doesn't match the bits"
+                    );
+                    Ok(value)
+                }
+            }
+
+            impl<F: Field> $int<F> {
+                pub fn constant(value: $native) -> Self {
+                    let mut bits = [Boolean::FALSE; $bits];
+                    let mut tmp = value;
+                    for bit in &mut bits {
+                        *bit = Boolean::constant((tmp & ONE) == ONE);
+                        tmp >>= 1_i32;
+                    }
+                    Self {
+                        bits,
+                        value: Some(value),
+                    }
+                }
+
+                pub fn from_bits_le(bits: &[Boolean<F>]) -> anyhow::Result<Self> {
+                    let bits = <&[Boolean<F>; $bits]>::try_from(bits)?.clone();
+
+                    let mut value: Option<$native> = Some(0);
+                    for (i, b) in bits.iter().enumerate() {
+                        value = match b.value().ok() {
+                            Some(b) => value.map(|v| v | (<$native>::from(b) << i)),
+                            None => None,
+                        }
+                    }
+
+                    Ok(Self { value, bits })
+                }
+            }
+
+            impl<F: Field> IntGadget<F> for $int<F> {
+                const BITS: usize = $bits;
+
+                fn constant(value: Self::Value) -> Self {
+                    Self::constant(value)
+                }
+
+                fn addmany(operands: &[Self]) -> anyhow::Result<Self> {
+                    let (first, rest) = operands
+                        .split_first()
+                        .ok_or_else(|| anyhow!("Cannot add an empty list of operands"))?;
+                    let mut sum = first.bits.to_vec();
+                    for operand in rest {
+                        (sum, _) = helpers::ripple_carry_add(&sum, &operand.bits, Boolean::FALSE)?;
+                    }
+                    Self::from_bits_le(&sum)
+                }
+
+                fn is_lt(&self, other: &Self) -> anyhow::Result<Boolean<F>> {
+                    helpers::is_less_than_signed(&self.bits, &other.bits)
+                }
+            }
+
+            impl<F: Field> ToBitsGadget<F> for $int<F> {
+                fn to_bits_le(&self) -> Result<Vec<Boolean<F>>, SynthesisError> {
+                    Ok(self.bits.to_vec())
+                }
+            }
+
+            impl<F: Field> EqGadget<F> for $int<F> {
+                #[tracing::instrument(target = "r1cs")]
+                fn is_eq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+                    self.bits.as_ref().is_eq(&other.bits)
+                }
+
+                #[tracing::instrument(target = "r1cs")]
+                fn conditional_enforce_equal(
+                    &self,
+                    other: &Self,
+                    condition: &Boolean<F>,
+                ) -> Result<(), SynthesisError> {
+                    self.bits.conditional_enforce_equal(&other.bits, condition)
+                }
+
+                #[tracing::instrument(target = "r1cs")]
+                fn conditional_enforce_not_equal(
+                    &self,
+                    other: &Self,
+                    condition: &Boolean<F>,
+                ) -> Result<(), SynthesisError> {
+                    self.bits
+                        .conditional_enforce_not_equal(&other.bits, condition)
+                }
+            }
+
+            impl<F: Field> CondSelectGadget<F> for $int<F> {
+                #[tracing::instrument(target = "r1cs", skip(cond, true_value, false_value))]
+                fn conditionally_select(
+                    cond: &Boolean<F>,
+                    true_value: &Self,
+                    false_value: &Self,
+                ) -> Result<Self, SynthesisError> {
+                    let selected_bits = true_value
+                        .bits
+                        .iter()
+                        .zip(&false_value.bits)
+                        .map(|(t, f)| cond.select(t, f));
+                    let mut bits = [Boolean::FALSE; $bits];
+                    for (result, new) in bits.iter_mut().zip(selected_bits) {
+                        *result = new?;
+                    }
+
+                    let value = cond.value().ok().and_then(|cond| {
+                        if cond {
+                            true_value.value().ok()
+                        } else {
+                            false_value.value().ok()
+                        }
+                    });
+                    Ok(Self { bits, value })
+                }
+            }
+        };
+    };
+}
+
+/// Implements `from_le_bytes` and `from_be_bytes` for a signed integer gadget
+/// of `$bytes` bytes, assembling it from `Int8`s without adding constraints.
+macro_rules! impl_from_bytes {
+    ($int:ident, $bytes:literal) => {
+        impl<F: ark_ff::Field> $int<F> {
+            /// Assembles the integer from its bytes, least significant first.
+            /// No constraints are added.
+            pub fn from_le_bytes(
+                bytes: &[$crate::gadgets::int8::Int8<F>; $bytes],
+            ) -> anyhow::Result<Self> {
+                let bits = bytes
+                    .iter()
+                    .flat_map(|byte| byte.bits.clone())
+                    .collect::<Vec<ark_r1cs_std::prelude::Boolean<F>>>();
+                Self::from_bits_le(&bits)
+            }
+
+            /// Assembles the integer from its bytes, most significant first
+            /// (network byte order). No constraints are added.
+            pub fn from_be_bytes(
+                bytes: &[$crate::gadgets::int8::Int8<F>; $bytes],
+            ) -> anyhow::Result<Self> {
+                let mut reversed_bytes = bytes.clone();
+                reversed_bytes.reverse();
+                Self::from_le_bytes(&reversed_bytes)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_from_bytes;
+pub(crate) use impl_signed_int;
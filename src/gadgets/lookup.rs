@@ -0,0 +1,87 @@
+use super::int8::Int8;
+use anyhow::{anyhow, Result};
+use ark_ff::Field;
+use ark_r1cs_std::{prelude::Boolean, select::CondSelectGadget};
+
+/// Returns `table[index]`, where `index` holds the little-endian bits of the
+/// index, by a select tree as deep as the index is wide.
+fn select_tree<F: Field>(index: &[Boolean<F>], table: &[i8]) -> Result<Int8<F>> {
+    let mut candidates = table
+        .iter()
+        .map(|entry| Int8::constant(*entry))
+        .collect::<Vec<Int8<F>>>();
+    // Each level halves the candidates, choosing within every pair by the
+    // next (least significant first) bit of the index.
+    for bit in index {
+        candidates = candidates
+            .chunks(2)
+            .map(|pair| match pair {
+                [low, high] => Ok(Int8::conditionally_select(bit, high, low)?),
+                _ => Err(anyhow!("Lookup tables must have a power of two entries")),
+            })
+            .collect::<Result<Vec<Int8<F>>>>()?;
+    }
+    candidates
+        .pop()
+        .ok_or_else(|| anyhow!("Error getting the selected entry"))
+}
+
+/// Returns `table[nibble]`, where `nibble` holds the little-endian bits of
+/// the index. The entry is picked by a depth-4 select tree, which is cheaper
+/// than a full byte lookup when the index only has 4 bits.
+pub fn nibble_lookup<F: Field>(nibble: &[Boolean<F>; 4], table: &[i8; 16]) -> Result<Int8<F>> {
+    select_tree(nibble, table)
+}
+
+/// Returns `table[index]`, reading `index` as unsigned. The entry is picked
+/// by a depth-8 select tree. Its first level only chooses between constants,
+/// so it is free, and the rest costs under a thousand constraints.
+pub fn byte_lookup<F: Field>(index: &Int8<F>, table: &[i8; 256]) -> Result<Int8<F>> {
+    select_tree(index.bits(), table)
+}
+
+#[cfg(test)]
+mod lookup_tests {
+    use super::{byte_lookup, nibble_lookup};
+    use crate::gadgets::{ConstraintF, Int8Gadget};
+    use ark_r1cs_std::{
+        prelude::{AllocVar, Boolean},
+        R1CSVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_nibble_lookup_selects_every_entry() {
+        let table = [
+            12_i8, -5, 0, 127, -128, 33, 7, -1, 64, 9, -64, 100, 3, -100, 55, 1,
+        ];
+        for (index, expected) in (0_u8..16).zip(table) {
+            let cs = ConstraintSystem::<ConstraintF>::new_ref();
+            let nibble = [0_u8, 1, 2, 3]
+                .map(|i| Boolean::new_witness(cs.clone(), || Ok((index >> i) & 1 == 1)).unwrap());
+
+            let entry = nibble_lookup(&nibble, &table).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(expected, entry.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_byte_lookup_selects_every_entry() {
+        let mut table = [0_i8; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            *entry = i8::from_ne_bytes([u8::try_from(i).unwrap()]).wrapping_mul(37) ^ 0x5a;
+        }
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        for (index, expected) in (0_u8..=255).zip(table) {
+            let index =
+                Int8Gadget::new_witness(cs.clone(), || Ok(i8::from_ne_bytes([index]))).unwrap();
+
+            let entry = byte_lookup(&index, &table).unwrap();
+
+            assert_eq!(expected, entry.value().unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
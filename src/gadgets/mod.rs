@@ -8,15 +8,38 @@ pub use address::Address;
 
 mod boolean;
 
+pub mod bytes;
+
+pub mod checksum;
+
+pub mod cipher;
+
+mod constant_pool;
+pub use constant_pool::ConstantPool;
+
+pub mod debug;
+
+pub mod encoding;
+
 mod field;
 
 mod helpers;
 
+pub mod lookup;
+
 mod poseidon;
-pub use poseidon::poseidon2_hash;
+pub use poseidon::{poseidon2_hash, ByteSponge};
 
-use self::int8::Int8;
+use self::{
+    int16::Int16,
+    int32::Int32,
+    int8::{AddFlags, Int8},
+};
 
+mod int16;
+mod int32;
+mod int_macros;
+pub use int32::{accumulate_i32, enforce_timestamps_monotonic, weighted_sum};
 mod int8;
 mod uint128;
 mod uint16;
@@ -28,6 +51,7 @@ pub mod traits;
 
 pub type ConstraintF = ark_ed_on_bls12_377::Fq;
 pub type Comparison = helpers::Comparison;
+pub type ByteSource = int8::ByteSource;
 
 pub type UInt8Gadget = UInt8<ConstraintF>;
 pub type UInt16Gadget = UInt16<ConstraintF>;
@@ -35,5 +59,9 @@ pub type UInt32Gadget = UInt32<ConstraintF>;
 pub type UInt64Gadget = UInt64<ConstraintF>;
 pub type UInt128Gadget = UInt128<ConstraintF>;
 pub type Int8Gadget = Int8<ConstraintF>;
+pub type Int16Gadget = Int16<ConstraintF>;
+pub type Int32Gadget = Int32<ConstraintF>;
+pub type AddFlagsGadget = AddFlags<ConstraintF>;
+pub type ConstantPoolGadget = ConstantPool<ConstraintF>;
 pub type AddressGadget = Address<ConstraintF>;
 pub type FieldGadget = FpVar<ConstraintF>;
@@ -1,7 +1,13 @@
-use super::ConstraintF;
+use super::{int8::Int8, ConstraintF};
 use crate::hash;
-use anyhow::{anyhow, Result};
-use ark_r1cs_std::{fields::fp::FpVar, R1CSVar};
+use anyhow::{anyhow, ensure, Result};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    fields::fp::FpVar,
+    prelude::{Boolean, FieldVar, ToBitsGadget},
+    R1CSVar,
+};
+use ark_relations::r1cs::ConstraintSystemRef;
 use ark_sponge::{
     constraints::{AbsorbGadget, CryptographicSpongeVar},
     poseidon::constraints::PoseidonSpongeVar,
@@ -30,10 +36,77 @@ pub fn poseidon2_hash(input: &impl AbsorbGadget<ConstraintF>) -> Result<FpVar<Co
         .cloned()
 }
 
+/// Byte oriented hashing on top of a Poseidon field sponge.
+///
+/// Every sponge absorbs its domain tag first, encoded like `absorb_bytes`
+/// does, so sponges created for different domains never produce the same
+/// output for the same input.
+#[derive(Clone)]
+pub struct ByteSponge<F: PrimeField> {
+    sponge: PoseidonSpongeVar<F>,
+}
+
+impl<F: PrimeField> ByteSponge<F> {
+    pub fn new(cs: ConstraintSystemRef<F>, domain: &[u8]) -> Result<Self> {
+        let sponge_params = hash::helpers::poseidon_parameters_for_test()?;
+        let mut sponge = PoseidonSpongeVar::new(cs, &sponge_params);
+        for chunk in domain.chunks(Self::bytes_per_field_element()?) {
+            sponge.absorb(&FpVar::constant(F::from_le_bytes_mod_order(chunk)))?;
+        }
+        sponge.absorb(&FpVar::constant(F::from(u64::try_from(domain.len())?)))?;
+        Ok(Self { sponge })
+    }
+
+    /// Number of bytes packed into each absorbed field element: the most that
+    /// always fit below the modulus, 31 for the fields used in this crate.
+    fn bytes_per_field_element() -> Result<usize> {
+        let bytes_per_field_element = (F::size_in_bits() - 1) / 8;
+        ensure!(
+            bytes_per_field_element > 0,
+            "The field is too small to pack bytes into its elements"
+        );
+        Ok(bytes_per_field_element)
+    }
+
+    /// Packs `bytes` little-endian into as few field elements as possible and
+    /// absorbs them, followed by the number of bytes so that inputs differing
+    /// only by trailing zeros are told apart.
+    pub fn absorb_bytes(&mut self, bytes: &[Int8<F>]) -> Result<()> {
+        for chunk in bytes.chunks(Self::bytes_per_field_element()?) {
+            let mut bits = Vec::with_capacity(chunk.len() * 8);
+            for byte in chunk {
+                bits.extend(byte.to_bits_le()?);
+            }
+            self.sponge.absorb(&Boolean::le_bits_to_fp_var(&bits)?)?;
+        }
+        self.sponge
+            .absorb(&FpVar::constant(F::from(u64::try_from(bytes.len())?)))?;
+        Ok(())
+    }
+
+    pub fn squeeze_bytes(&mut self, n: usize) -> Result<Vec<Int8<F>>> {
+        self.sponge
+            .squeeze_bytes(n)
+            .map_err(|e| anyhow!(e.to_string()))?
+            .iter()
+            .map(|byte| Int8::pack_bits(&byte.to_bits_le()?))
+            .collect()
+    }
+
+    pub fn squeeze_field_element(&mut self) -> Result<FpVar<F>> {
+        self.sponge
+            .squeeze_field_elements(1)
+            .map_err(|e| anyhow!(e.to_string()))?
+            .pop()
+            .ok_or_else(|| anyhow!("Error squeezing a field element"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::ByteSponge;
     use crate::{
-        gadgets::{self, UInt8Gadget},
+        gadgets::{self, ConstraintF, Int8Gadget, UInt8Gadget},
         hash,
     };
     use ark_r1cs_std::R1CSVar;
@@ -52,4 +125,69 @@ mod tests {
         assert!(cs.is_satisfied().unwrap());
         assert_eq!(squeeze_var.value().unwrap(), primitive_squeeze);
     }
+
+    #[test]
+    fn test_byte_sponge_is_deterministic() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let message = Int8Gadget::new_witness_vec(cs.clone(), &[72_i8, -1, 0, 127, -128]).unwrap();
+
+        let mut sponge = ByteSponge::new(cs.clone(), b"test").unwrap();
+        sponge.absorb_bytes(&message).unwrap();
+        let digest = sponge.squeeze_bytes(32).unwrap();
+
+        let mut same_sponge = ByteSponge::new(cs.clone(), b"test").unwrap();
+        same_sponge.absorb_bytes(&message).unwrap();
+        let same_digest = same_sponge.squeeze_bytes(32).unwrap();
+
+        let mut other_domain_sponge = ByteSponge::new(cs.clone(), b"other").unwrap();
+        other_domain_sponge.absorb_bytes(&message).unwrap();
+        let other_domain_digest = other_domain_sponge.squeeze_bytes(32).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(32, digest.len());
+        assert_eq!(digest.value().unwrap(), same_digest.value().unwrap());
+        assert_ne!(
+            digest.value().unwrap(),
+            other_domain_digest.value().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_byte_sponge_distinguishes_domains_differing_by_trailing_zeros() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let message = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3]).unwrap();
+
+        let mut sponge = ByteSponge::new(cs.clone(), b"a").unwrap();
+        sponge.absorb_bytes(&message).unwrap();
+        let mut padded_domain_sponge = ByteSponge::new(cs.clone(), b"a\0").unwrap();
+        padded_domain_sponge.absorb_bytes(&message).unwrap();
+
+        assert_ne!(
+            sponge.squeeze_bytes(8).unwrap().value().unwrap(),
+            padded_domain_sponge
+                .squeeze_bytes(8)
+                .unwrap()
+                .value()
+                .unwrap()
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_byte_sponge_distinguishes_trailing_zeros() {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let message = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3]).unwrap();
+        let padded_message = Int8Gadget::new_witness_vec(cs.clone(), &[1_i8, 2, 3, 0]).unwrap();
+
+        let mut sponge = ByteSponge::new(cs.clone(), b"test").unwrap();
+        sponge.absorb_bytes(&message).unwrap();
+        let mut padded_sponge = ByteSponge::new(cs.clone(), b"test").unwrap();
+        padded_sponge.absorb_bytes(&padded_message).unwrap();
+
+        assert_ne!(
+            sponge.squeeze_bytes(8).unwrap().value().unwrap(),
+            padded_sponge.squeeze_bytes(8).unwrap().value().unwrap()
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
 }
@@ -1,6 +1,10 @@
 use anyhow::{anyhow, Result};
 use ark_ff::Field;
-use ark_r1cs_std::{prelude::Boolean, uint8::UInt8, ToBitsGadget, ToBytesGadget};
+use ark_r1cs_std::{
+    prelude::{Boolean, EqGadget},
+    uint8::UInt8,
+    R1CSVar, ToBitsGadget, ToBytesGadget,
+};
 use ark_relations::r1cs::ConstraintSystemRef;
 
 use super::Comparison;
@@ -145,6 +149,24 @@ pub trait ComparisonGadget<F: Field> {
         Self: std::marker::Sized;
 }
 
+/// Common interface of the signed integer gadgets, so algorithms (e.g. sorting
+/// networks) can be written once for every width.
+pub trait IntGadget<F: Field>: Clone + EqGadget<F> + R1CSVar<F> + ToBitsGadget<F>
+where
+    Self: std::marker::Sized,
+{
+    /// Number of bits of the integer.
+    const BITS: usize;
+
+    fn constant(value: Self::Value) -> Self;
+
+    /// Wrapping sum of all the operands.
+    fn addmany(operands: &[Self]) -> Result<Self>;
+
+    /// Signed `self < other`.
+    fn is_lt(&self, other: &Self) -> Result<Boolean<F>>;
+}
+
 /* ToFieldElements implementations */
 
 impl<F: Field> ToFieldElements<F> for u8 {
@@ -255,9 +277,11 @@ impl<F: Field> ToFieldElements<F> for [u8; 63] {
 
 #[cfg(test)]
 mod test {
-    use super::ToFieldElements;
-    use crate::gadgets::ConstraintF;
+    use super::{IntGadget, ToFieldElements};
+    use crate::gadgets::{ConstraintF, Int16Gadget, Int32Gadget, Int8Gadget};
     use ark_ff::{One, Zero};
+    use ark_r1cs_std::{prelude::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
 
     #[test]
     fn test_u8_to_field_elements() {
@@ -539,4 +563,48 @@ mod test {
             ToFieldElements::<ConstraintF>::to_field_elements(&address).unwrap()
         )
     }
+
+    fn check_int_gadget<G>(
+        left: G::Value,
+        right: G::Value,
+        expected_sum: G::Value,
+        expected_is_lt: bool,
+    ) where
+        G: IntGadget<ConstraintF> + AllocVar<G::Value, ConstraintF>,
+        G::Value: Copy + PartialEq + std::fmt::Debug,
+    {
+        let cs = ConstraintSystem::<ConstraintF>::new_ref();
+        let left_var = G::new_witness(cs.clone(), || Ok(left)).unwrap();
+        let right_var = G::new_witness(cs.clone(), || Ok(right)).unwrap();
+
+        let sum = G::addmany(&[left_var.clone(), right_var.clone(), G::constant(left)]).unwrap();
+        let is_lt = left_var.is_lt(&right_var).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(expected_sum, sum.value().unwrap());
+        assert_eq!(expected_is_lt, is_lt.value().unwrap());
+        assert_eq!(G::BITS, sum.to_bits_le().unwrap().len());
+    }
+
+    #[test]
+    fn test_int8_as_int_gadget() {
+        check_int_gadget::<Int8Gadget>(3, 4, 10, true);
+        check_int_gadget::<Int8Gadget>(-128, 127, 127, true);
+        check_int_gadget::<Int8Gadget>(127, -128, 126, false);
+        check_int_gadget::<Int8Gadget>(5, 5, 15, false);
+    }
+
+    #[test]
+    fn test_int16_as_int_gadget() {
+        check_int_gadget::<Int16Gadget>(300_i16, -1000_i16, -400_i16, false);
+        check_int_gadget::<Int16Gadget>(i16::MIN, i16::MAX, i16::MAX, true);
+        check_int_gadget::<Int16Gadget>(32_000_i16, 1_000_i16, -536_i16, false);
+    }
+
+    #[test]
+    fn test_int32_as_int_gadget() {
+        check_int_gadget::<Int32Gadget>(3_i32, 4_i32, 10_i32, true);
+        check_int_gadget::<Int32Gadget>(i32::MIN, i32::MAX, i32::MAX, true);
+        check_int_gadget::<Int32Gadget>(-7_i32, -8_i32, -22_i32, false);
+    }
 }